@@ -1,5 +1,7 @@
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::io::prelude::*;
 use std::iter::Iterator;
 use zip::result::ZipError;
@@ -7,33 +9,89 @@ use std::io::{Seek, Write};
 use zip::write::FileOptions;
 use walkdir::{DirEntry, WalkDir};
 
-const METHOD_DEFLATED: Option<zip::CompressionMethod> = Some(zip::CompressionMethod::Deflated);
+use crate::constants::*;
+use crate::output::TsvRows;
+
+// A backup archive found in the backups directory
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupFile {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupList {
+    pub backups: Vec<BackupFile>,
+}
+
+impl TsvRows for BackupList {
+    fn tsv_header(&self) -> Vec<&'static str> {
+        vec!["name", "path", "size", "modified"]
+    }
+
+    fn tsv_rows(&self) -> Vec<Vec<String>> {
+        self.backups.iter().map(|b| vec![
+            b.name.to_string(),
+            b.path.to_string_lossy().to_string(),
+            b.size.to_string(),
+            b.modified.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        ]).collect()
+    }
+}
+
+const METHOD_DEFLATED: zip::CompressionMethod = zip::CompressionMethod::Deflated;
 
 // zip = "0.6.4"
 // Used from example in zip-rs:
 // https://github.com/zip-rs/zip/blob/master/examples/write_dir.rs
+// A single file is stored at the root of the archive
 pub(crate) fn create_backup(src_dir: &Path, dst_file: &Path) -> zip::result::ZipResult<()> {
-    
-    let method: zip::CompressionMethod = METHOD_DEFLATED.unwrap();
-    
-    if !src_dir.is_dir() {
-        return Err(ZipError::FileNotFound);
-    }
+    let prefix = match src_dir.is_file() {
+        true => src_dir.parent().ok_or(ZipError::FileNotFound)?,
+        false if src_dir.is_dir() => src_dir,
+        false => return Err(ZipError::FileNotFound),
+    };
 
     let path = Path::new(dst_file);
-    let file = File::create(path).unwrap();
+    let file = File::create(path)?;
 
     let walk_dir = WalkDir::new(src_dir);
     let it = walk_dir.into_iter();
 
-    zip_dir(&mut it.filter_map(|e| e.ok()), src_dir.to_str().unwrap(), file, method)?;
+    zip_dir(&mut it.filter_map(|e| e.ok()), prefix, file, METHOD_DEFLATED)?;
 
     Ok(())
 }
 
+// List the zip archives in backup_dir, newest first
+pub(crate) fn list_backups(backup_dir: &Path) -> std::io::Result<BackupList> {
+    let mut backups: Vec<BackupFile> = Vec::new();
+
+    if backup_dir.exists() {
+        for entry in std::fs::read_dir(backup_dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != ZIP) { continue; }
+            let Some(name) = path.file_stem() else { continue };
+
+            let metadata = std::fs::metadata(&path)?;
+            backups.push(BackupFile {
+                name: name.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                path,
+            });
+        }
+    }
+
+    backups.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.name.cmp(&b.name)));
+    Ok(BackupList { backups })
+}
+
 fn zip_dir<T>(
     it: &mut dyn Iterator<Item=DirEntry>,
-    prefix: &str,
+    prefix: &Path,
     writer: T,
     method: zip::CompressionMethod,
 ) -> zip::result::ZipResult<()>
@@ -48,7 +106,7 @@ fn zip_dir<T>(
     let mut buffer = Vec::new();
     for entry in it {
         let path = entry.path();
-        let name = path.strip_prefix(Path::new(prefix)).unwrap();
+        let Ok(name) = path.strip_prefix(prefix) else { continue };
 
         // Write file or directory explicitly
         // Some unzip tools unzip files with directory paths correctly, some do not!
@@ -99,6 +157,26 @@ mod tests {
         create_backup(dir.path(), backup_path.as_path()).unwrap();
         
         assert!(backup_path.exists());
+
+        let list = list_backups(dir.path()).unwrap();
+        assert_eq!(list.backups.len(), 1);
+        assert_eq!(list.backups[0].name, "backup");
+        dir.close().unwrap();
+    }
+
+    // Test that backing up a single file leaves out the rest of its directory
+    #[test]
+    fn test_create_file_backup() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("unrelated")).unwrap();
+        std::fs::write(dir.path().join("unrelated").join("notes.txt"), "").unwrap();
+        std::fs::write(dir.path().join("minimal.lua"), "").unwrap();
+
+        let backup_path = dir.path().join("backup.zip");
+        create_backup(&dir.path().join("minimal.lua"), &backup_path).unwrap();
+
+        let archive = zip::ZipArchive::new(File::open(&backup_path).unwrap()).unwrap();
+        assert_eq!(archive.file_names().collect::<Vec<_>>(), vec!["minimal.lua"]);
         dir.close().unwrap();
     }
 }
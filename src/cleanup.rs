@@ -0,0 +1,335 @@
+use std::path::{Component, Path, PathBuf};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use walkdir::WalkDir;
+use fs_extra::dir::{CopyOptions, move_dir};
+
+use crate::constants::*;
+use crate::settings::Settings;
+use crate::configs::{ConfigData, Configs};
+use crate::status::{self, PathKind};
+
+// A directory scheduled for removal along with its size on disk
+#[derive(Debug, Clone)]
+pub struct CleanupTarget {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl CleanupTarget {
+    pub fn new(path: PathBuf) -> CleanupTarget {
+        let size = dir_size(&path);
+        CleanupTarget { path, size }
+    }
+}
+
+// --| Purge Targets ----------------------------
+// Collect the per-config data, cache and state directories of a config,
+// and optionally the config directory itself. Only existing paths are returned.
+pub(crate) fn purge_targets(cfg: &ConfigData, settings: &Settings, include_config: bool) -> Vec<CleanupTarget> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    // --| A repro keeps its data, state and cache together in the .repro root next to its file
+    let repro_root = Path::new(&cfg.path).join(REPRO_ROOT);
+    if cfg.entry.is_some() && repro_root.is_dir() {
+        paths.push(repro_root);
+    } else {
+        if let Some(data_path) = &cfg.data_path {
+            paths.push(PathBuf::from(data_path));
+        }
+
+        // --| cache_path is usually the shared ncm cache root, only its <name> child belongs to the config
+        paths.extend(status::owned_path(PathKind::Cache, cfg, settings));
+        paths.extend(status::owned_path(PathKind::State, cfg, settings));
+    }
+
+    // --| A config registered from elsewhere is left in place by the safety check below
+    if include_config {
+        paths.push(PathBuf::from(&cfg.path));
+    }
+
+    let mut targets: Vec<CleanupTarget> = Vec::new();
+    for path in paths {
+        if (path.exists() || path.is_symlink()) && is_safe_to_remove(&path, settings) && !targets.iter().any(|t| t.path == path) {
+            targets.push(CleanupTarget::new(path));
+        }
+    }
+    targets
+}
+
+// --| Find Orphans -----------------------------
+// Directories directly below the ncm data, cache and state roots which are
+// not named after, and do not contain the paths of, any registered config
+pub(crate) fn find_orphans(configs: &Configs, settings: &Settings) -> Vec<CleanupTarget> {
+    let roots = [&settings.ncm_paths.local, &settings.ncm_paths.cache, &settings.ncm_paths.state];
+    let mut orphans: Vec<CleanupTarget> = Vec::new();
+
+    for root in roots {
+        let entries = match std::fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_dir() || orphans.iter().any(|o| o.path == path) { continue; }
+
+            let referenced = configs.configs.iter().any(|cfg| {
+                entry.file_name() == cfg.name.as_str()
+                    || Path::new(&cfg.path).starts_with(&path)
+                    || cfg.data_path.as_ref().is_some_and(|d| Path::new(d).starts_with(&path))
+                    || cfg.cache_path.as_ref().is_some_and(|c| Path::new(c).starts_with(&path))
+            });
+
+            if !referenced && is_safe_to_remove(&path, settings) {
+                orphans.push(CleanupTarget::new(path));
+            }
+        }
+    }
+
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+    orphans
+}
+
+// --| Safety Check -----------------------------
+// Only paths strictly inside one of the ncm roots may be removed, and never
+// anything above one of the ncm/nvim roots. Paths are compared after resolving
+// '.' and '..', so a config named '..' can not reach the directory above a root.
+pub(crate) fn is_safe_to_remove(path: &Path, settings: &Settings) -> bool {
+    let protected = [
+        &settings.base_paths.config, &settings.base_paths.local, &settings.base_paths.cache, &settings.base_paths.state,
+        &settings.nvim_paths.config, &settings.nvim_paths.local, &settings.nvim_paths.cache, &settings.nvim_paths.state,
+        &settings.ncm_cfg_path,
+    ];
+    let ncm_roots = [&settings.ncm_paths.config, &settings.ncm_paths.local, &settings.ncm_paths.cache, &settings.ncm_paths.state];
+
+    let path = normalize(path);
+    if path.as_os_str().is_empty() || !path.is_absolute() { return false; }
+
+    let inside = ncm_roots.iter()
+        .map(|root| normalize(root))
+        .any(|root| !root.as_os_str().is_empty() && path != root && path.starts_with(&root));
+
+    inside && !protected.iter().chain(ncm_roots.iter()).any(|root| normalize(root).starts_with(&path))
+}
+
+// Resolve '.' and '..' without touching the filesystem, the last path
+// component may be a symlink which must be removed rather than followed
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => { normalized.pop(); }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// --| Remove Target ----------------------------
+// Delete a path, or move it into `trash_dir` when one is given
+pub(crate) fn remove_target(path: &Path, trash_dir: Option<&Path>) -> Result<()> {
+    match trash_dir {
+        Some(trash) => {
+            std::fs::create_dir_all(trash)?;
+            let file_name = path.file_name().ok_or_else(|| anyhow!("{}: {:?}", ERR_CLEANUP_REMOVE, path))?;
+            let stamped = format!("{}-{}", file_name.to_string_lossy(), Utc::now().format(TRASH_TIMESTAMP_FORMAT));
+
+            // --| Data and cache directories share the config's name
+            let mut destination = trash.join(&stamped);
+            let mut suffix = 1;
+            while destination.exists() {
+                destination = trash.join(format!("{stamped}-{suffix}"));
+                suffix += 1;
+            }
+
+            move_path(path, &destination)?;
+        }
+        None => {
+            if path.is_dir() && !path.is_symlink() {
+                std::fs::remove_dir_all(path)?;
+            } else {
+                std::fs::remove_file(path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// --| Move Path --------------------------------
+// Move a directory to destination, which must not exist yet
+pub(crate) fn move_path(path: &Path, destination: &Path) -> Result<()> {
+    // --| rename fails across filesystems, fall back to copying
+    if std::fs::rename(path, destination).is_err() {
+        std::fs::create_dir_all(destination)?;
+        move_dir(path, destination, &CopyOptions::new().content_only(true))
+            .map_err(|e| anyhow!("{}: {:?} {e}", ERR_MOVE, path))?;
+    }
+    Ok(())
+}
+
+// --| Size Helpers -----------------------------
+// Total size of all files below path, without following symlinks
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path).into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 { format!("{bytes} {}", UNITS[0]) } else { format!("{size:.1} {}", UNITS[unit]) }
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use pretty_assertions::assert_eq;
+
+    // Test that sizes are summed recursively and formatted
+    #[test]
+    fn test_dir_size() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("nested")).unwrap();
+
+        let mut file = File::create(dir.path().join("one.txt")).unwrap();
+        file.write_all(&[0; 1024]).unwrap();
+        let mut file = File::create(dir.path().join("nested").join("two.txt")).unwrap();
+        file.write_all(&[0; 512]).unwrap();
+
+        assert_eq!(dir_size(dir.path()), 1536);
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(12), "12 B");
+
+        dir.close().unwrap();
+    }
+
+    // Test that purging only picks up the config's own directories
+    #[test]
+    fn test_purge_targets() {
+        let dir = tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.ncm_paths.local = dir.path().join("data");
+        settings.ncm_paths.cache = dir.path().join("cache");
+        settings.ncm_paths.state = dir.path().join("state");
+        settings.ncm_paths.config = dir.path().join("ncm-configs");
+
+        let config_dir = dir.path().join("configs").join("test");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::create_dir_all(settings.ncm_paths.local.join("test")).unwrap();
+        std::fs::create_dir_all(settings.ncm_paths.cache.join("test")).unwrap();
+        std::fs::create_dir_all(settings.ncm_paths.cache.join("other")).unwrap();
+
+        let cfg = ConfigData {
+            name: "test".to_string(),
+            path: config_dir.to_str().unwrap().to_string(),
+            data_path: Some(settings.ncm_paths.local.join("test").to_str().unwrap().to_string()),
+            cache_path: Some(settings.ncm_paths.cache.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+
+        let targets: Vec<PathBuf> = purge_targets(&cfg, &settings, false).into_iter().map(|t| t.path).collect();
+        assert_eq!(targets, vec![settings.ncm_paths.local.join("test"), settings.ncm_paths.cache.join("test")]);
+
+        // --| A config directory outside the ncm roots is never removed
+        let targets = purge_targets(&cfg, &settings, true);
+        assert_eq!(targets.len(), 2);
+
+        let managed_dir = settings.ncm_paths.config.join("test");
+        std::fs::create_dir_all(&managed_dir).unwrap();
+        let managed = ConfigData { path: managed_dir.to_str().unwrap().to_string(), ..cfg.clone() };
+        let targets: Vec<PathBuf> = purge_targets(&managed, &settings, true).into_iter().map(|t| t.path).collect();
+        assert_eq!(targets.last(), Some(&managed_dir));
+
+        // --| A name of '..' must not resolve to the directories above the ncm roots
+        std::fs::create_dir_all(settings.ncm_paths.state.join("test")).unwrap();
+        let parent = ConfigData { name: "..".to_string(), data_path: None, cache_path: None, ..cfg.clone() };
+        assert!(purge_targets(&parent, &settings, false).is_empty());
+        assert!(!is_safe_to_remove(&settings.ncm_paths.cache.join(".."), &settings));
+        assert!(!is_safe_to_remove(&settings.ncm_paths.state.join("test").join("..").join(".."), &settings));
+        assert!(!is_safe_to_remove(&settings.ncm_paths.state, &settings));
+        assert!(is_safe_to_remove(&settings.ncm_paths.state.join("test"), &settings));
+
+        // --| A repro is purged through its .repro root
+        let repro_dir = settings.ncm_paths.config.join("repro");
+        crate::repro::write(&["folke/flash.nvim".to_string()], &repro_dir).unwrap();
+        let repro_root = repro_dir.join(REPRO_ROOT);
+        let repro = ConfigData {
+            name: "repro".to_string(),
+            path: repro_dir.to_str().unwrap().to_string(),
+            data_path: Some(repro_root.join(XDG_DATA).to_str().unwrap().to_string()),
+            cache_path: Some(repro_root.join(XDG_CACHE).to_str().unwrap().to_string()),
+            entry: Some(REPRO_LUA.to_string()),
+            ..Default::default()
+        };
+        let targets: Vec<PathBuf> = purge_targets(&repro, &settings, true).into_iter().map(|t| t.path).collect();
+        assert_eq!(targets, vec![repro_root, repro_dir]);
+
+        // --| Trash moves instead of deleting -
+        let trash = dir.path().join("trash");
+        remove_target(&config_dir, Some(&trash)).unwrap();
+        remove_target(&settings.ncm_paths.local.join("test"), Some(&trash)).unwrap();
+        assert!(!config_dir.exists());
+        assert_eq!(std::fs::read_dir(&trash).unwrap().count(), 2);
+
+        remove_target(&settings.ncm_paths.cache.join("test"), None).unwrap();
+        assert!(!settings.ncm_paths.cache.join("test").exists());
+        assert!(settings.ncm_paths.cache.join("other").exists());
+
+        dir.close().unwrap();
+    }
+
+    // Test that only unreferenced directories are reported as orphans
+    #[test]
+    fn test_find_orphans() {
+        let dir = tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.ncm_paths.local = dir.path().join("data");
+        settings.ncm_paths.cache = dir.path().join("cache");
+        settings.ncm_paths.state = dir.path().join("state");
+
+        for path in ["data/main", "data/removed", "data/custom_data", "cache/main", "cache/removed"] {
+            std::fs::create_dir_all(dir.path().join(path)).unwrap();
+        }
+
+        let configs = Configs {
+            configs_default: "main".to_string(),
+            configs: vec![
+                ConfigData {
+                    name: "main".to_string(),
+                    path: dir.path().join("configs").join("main").to_str().unwrap().to_string(),
+                    data_path: Some(settings.ncm_paths.local.join("main").to_str().unwrap().to_string()),
+                    cache_path: Some(settings.ncm_paths.cache.to_str().unwrap().to_string()),
+                    ..Default::default()
+                },
+                ConfigData {
+                    name: "other".to_string(),
+                    path: dir.path().join("configs").join("other").to_str().unwrap().to_string(),
+                    data_path: Some(settings.ncm_paths.local.join("custom_data").to_str().unwrap().to_string()),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let orphans: Vec<PathBuf> = find_orphans(&configs, &settings).into_iter().map(|o| o.path).collect();
+        assert_eq!(orphans, vec![dir.path().join("cache/removed"), dir.path().join("data/removed")]);
+
+        dir.close().unwrap();
+    }
+}
@@ -1,23 +1,38 @@
-use std::str::FromStr;
+use std::cmp::Reverse;
 use ansi_term::Color::RGB;
-use anyhow::{anyhow, Result};
 use log::{debug, error, info};
 use inquire::ui::RenderConfig;
 use std::path::{Path, PathBuf};
 use std::sync::RwLockWriteGuard;
 use ansi_term::ANSIGenericString;
-use spinners::{Spinner, Spinners};
+use spinners::{Spinner, Spinners, Stream};
 use prettytable::format::Alignment;
 use inquire::{Confirm, Select, Text};
-use clap::{command, Subcommand, Parser};
-use fs_extra::dir::{CopyOptions, move_dir};
+use chrono::{DateTime, Duration, Local, Utc};
+use clap::{Subcommand, Parser, ValueEnum};
+use fs_extra::dir::CopyOptions;
 use prettytable::{Attr, Cell, color, Row, Table};
 
 use crate::configs;
+use crate::cleanup;
+use crate::repair;
+use crate::status::{self, LinkState};
+use crate::doctor::{self, CheckStatus, DoctorReport};
+use crate::history;
+use crate::prompt;
+use crate::templates;
+use crate::teardown;
+use crate::git;
+use crate::launch;
+use crate::trial;
+use crate::repro;
+use crate::setup::{self, SetupProgress, SetupStage};
 use crate::constants::*;
 use crate::settings::Settings;
-use crate::backup::create_backup;
-use crate::configs::{BackupInfo, ConfigData, Configs};
+use crate::errors::{NcmError, Result};
+use crate::output::{self, OutputFormat};
+use crate::backup::{self, create_backup};
+use crate::configs::{ConfigData, Configs};
 
 #[derive(Parser)]
 #[command(name = "ncm")]
@@ -36,160 +51,1288 @@ EXAMPLES:
 pub struct NvCfgArgs {
     #[command(subcommand)]
     pub(crate) command: Commands,
+
+    /// Output format for list, status, backup list and doctor (logs go to stderr)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub(crate) format: OutputFormat,
+
+    /// Answer yes to confirmation prompts, and use the defaults during first-time setup
+    #[arg(short, long, global = true)]
+    pub(crate) yes: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Adds new configuration directory, referenced by name 
-    Add { name: String, path: PathBuf, description: Option<String> },
+    Add {
+        #[arg(required_unless_present = "worktree")]
+        name: Option<String>,
+        /// Configuration directory (with --git the description can be given here instead)
+        #[arg(required_unless_present_any = ["git", "worktree"], conflicts_with = "git")]
+        path: Option<PathBuf>,
+        description: Option<String>,
+        /// Description of the configuration, for when no path is given (e.g. with --git)
+        #[arg(short = 'd', long = "description", value_name = "DESCRIPTION", conflicts_with = "description")]
+        description_flag: Option<String>,
+        /// Clone the configuration from this git url into the ncm configs directory
+        #[arg(long)]
+        git: Option<String>,
+        /// Branch, tag or commit to check out after cloning
+        #[arg(long = "ref", requires = "git")]
+        git_ref: Option<String>,
+        /// Check out a branch of an existing git config as a new config in its own worktree
+        #[arg(long, num_args = 3, value_names = ["CONFIG", "BRANCH", "NEW_NAME"], conflicts_with_all = ["name", "path", "description", "git"])]
+        worktree: Option<Vec<String>>,
+    },
 
     /// Remove a configuration from the config store
-    Remove { name: Option<String> },
-
-    /// Load a configuration by name from the configuration store
-    Load { name: Option<String> },
+    Remove {
+        name: Option<String>,
+        /// Also delete the configuration's data and cache directories
+        #[arg(long)]
+        purge: bool,
+        /// With --purge, also delete the configuration directory itself
+        #[arg(long, requires = "purge")]
+        include_config: bool,
+        /// With --purge, move the directories into the ncm trash instead of deleting them
+        #[arg(long, requires = "purge")]
+        trash: bool,
+    },
+
+    /// Load a configuration by name from the configuration store ('-' loads the previous one)
+    #[command(alias = "switch")]
+    Load {
+        name: Option<String>,
+        /// Only offer configurations with this tag when selecting
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Launch nvim with a configuration in its own isolated environment, without loading it
+    Run {
+        name: String,
+        /// Arguments passed on to nvim
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
 
     /// List current stored configurations
-    List,
+    List {
+        /// Only list configurations with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Order in which to list the configurations
+        #[arg(short, long, value_enum)]
+        sort: Option<ListSort>,
+    },
 
     /// Setup NCM for the first time
-    Setup,
+    Setup {
+        /// Never prompt, use the given values or their defaults
+        #[arg(long)]
+        non_interactive: bool,
+        /// Directory the original configuration is moved into
+        #[arg(long)]
+        config_root: Option<PathBuf>,
+        /// Name the original configuration is stored under
+        #[arg(long)]
+        name: Option<String>,
+        /// Do not create a zip backup of the original configuration
+        #[arg(long)]
+        no_backup: bool,
+        /// Continue an interrupted setup without asking
+        #[arg(long, conflicts_with = "rollback")]
+        resume: bool,
+        /// Undo an interrupted setup, restoring the original configuration
+        #[arg(long)]
+        rollback: bool,
+        /// Without an existing nvim config, add this directory as the first configuration
+        #[arg(long, conflicts_with = "template")]
+        from: Option<PathBuf>,
+        /// Without an existing nvim config, create the first configuration from a built-in template
+        #[arg(long)]
+        template: Option<String>,
+    },
 
     /// Backup all, selected, or current configuration
-    Backup { name: Option<String> },
+    #[command(args_conflicts_with_subcommands = true)]
+    Backup {
+        #[command(subcommand)]
+        action: Option<BackupAction>,
+        name: Option<String>,
+        /// Only offer (or back up all) configurations with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// List recently loaded configurations
+    History {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+    },
+
+    /// Find (and optionally delete) data directories no configuration refers to
+    Gc {
+        /// Delete the orphaned directories after confirmation (default is a dry run)
+        #[arg(long)]
+        delete: bool,
+        /// With --delete, move the directories into the ncm trash instead
+        #[arg(long, requires = "delete")]
+        trash: bool,
+    },
+
+    /// Check the installation for problems, exits non-zero if any check fails
+    Doctor,
+
+    /// Show which configuration the nvim config, data, cache and state paths belong to
+    Status {
+        /// Print only the current configuration name (with a trailing * on drift), for shell prompts
+        #[arg(short, long)]
+        short: bool,
+    },
+
+    /// Fix the problems reported by doctor where possible
+    Repair,
+
+    /// Create a new configuration from a template
+    New {
+        name: String,
+        /// Built in template (minimal, kickstart-style, lazy-bootstrap, vimscript) or one from the user templates directory
+        #[arg(short, long)]
+        template: Option<String>,
+    },
+
+    /// Launch nvim with a temporary configuration, then keep or delete it
+    Try {
+        /// Configuration directory or git url
+        source: String,
+        /// Name to register the trial under (defaults to try-<timestamp>)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Let 'ncm gc' remove the trial after this long if it is not kept (e.g. 90m, 12h, 7d)
+        #[arg(long, value_parser = trial::parse_ttl)]
+        ttl: Option<Duration>,
+    },
+
+    /// Generate a minimal single-file config reproducing a plugin issue
+    Repro {
+        name: String,
+        /// Plugin to install, as owner/repo or a git url (repeatable)
+        #[arg(short, long = "plugin", required = true, value_parser = repro::parse_spec)]
+        plugins: Vec<String>,
+        /// Launch nvim with the reproduction instead of printing the command
+        #[arg(long)]
+        run: bool,
+        /// Let 'ncm gc' remove the reproduction after this long (e.g. 90m, 12h, 7d)
+        #[arg(long, value_parser = trial::parse_ttl)]
+        ttl: Option<Duration>,
+    },
+
+    /// Fetch and fast-forward configurations added with --git
+    Update {
+        #[arg(required_unless_present = "all")]
+        name: Option<String>,
+        /// Update every configuration added with --git
+        #[arg(long, conflicts_with = "name")]
+        all: bool,
+    },
+
+    /// Pin a configuration to a commit, tag or branch (the current commit by default)
+    Pin { name: String, rev: Option<String> },
+
+    /// Remove the pin from a configuration
+    Unpin { name: String },
+
+    /// Put one configuration back into the real nvim directories and remove ncm
+    #[command(alias = "uninstall")]
+    Teardown {
+        /// Configuration to keep (defaults to the current default)
+        name: Option<String>,
+        /// Zip the other configurations into this directory before they are removed
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Delete the other configurations stored in the ncm directories without archiving them
+        #[arg(long)]
+        discard_others: bool,
+    },
+
+    /// Add or remove tags on a configuration
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Update the stored description, path or data path of a configuration
+    #[command(alias = "edit")]
+    Set {
+        name: String,
+        /// New description for the configuration
+        #[arg(short, long)]
+        description: Option<String>,
+        /// New configuration directory (must contain init.lua or init.vim), or a .lua or .vim file
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// New data directory for the configuration
+        #[arg(long)]
+        data_path: Option<PathBuf>,
+        /// Prompt for each value, pre-filled with the current one
+        #[arg(short, long)]
+        interactive: bool,
+    },
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum ListSort {
+    /// Most recently loaded first
+    LastUsed,
+    /// Alphabetically by name
+    Name,
+    /// Most recently added first
+    Created,
+}
+
+#[derive(Subcommand)]
+pub enum BackupAction {
+    /// List existing backup archives
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum TagAction {
+    /// Add one or more tags to a configuration
+    Add { name: String, #[arg(required = true)] tags: Vec<String> },
+
+    /// Remove one or more tags from a configuration
+    Remove { name: String, #[arg(required = true)] tags: Vec<String> },
 }
 
 // --| Add ---------------------------------
 // --|--------------------------------------
-pub(crate) fn add_config(name: &str, path: &Path, description: &Option<String>, data_path: Option<String>, cache_path: Option<String>, config_json: &str) {
-    let mut data_path_buf = PathBuf::from(data_path.unwrap());
-    data_path_buf.push(name);
-    let data_path = Option::from(data_path_buf.to_str().unwrap().to_string());
-    let data_path_str = data_path.clone().unwrap();
+pub(crate) fn add_config(name: &str, path: &Path, description: &Option<String>, data_path: Option<String>, cache_path: Option<String>, config_json: &str) -> Result<()> {
+    configs::validate_name(name)?;
+    let (path, entry) = split_entry(path)?;
+
+    let data_path = match data_path {
+        Some(d) => Some(path_str(&PathBuf::from(d).join(name))?.to_string()),
+        None => None,
+    };
+    let data_path_str = data_path.clone().unwrap_or_default();
 
-    if configs::add_config(
+    configs::add_config(
         config_json,
         ConfigData {
-            name: name.parse().unwrap(),
-            path: path.to_str().unwrap().to_string(),
+            name: name.to_string(),
+            path: path_str(&path)?.to_string(),
             description: description.clone(),
             data_path,
             cache_path,
+            entry: entry.clone(),
+            ..Default::default()
+        },
+    )?;
+
+    info!("{}: {name:?} {path:?} {description:?} {data_path_str:?} ", INFO_CONFIGS_ADDED);
+    if entry.is_some() {
+        info!("{} 'ncm run {name}'", INFO_ENTRY_RUN);
+    }
+    Ok(())
+}
+
+// Clone a git repository into the ncm configs root and register it,
+// remembering the remote and ref so it can be updated later
+pub(crate) fn add_git_config(name: &str, url: &str, git_ref: &Option<String>, description: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    configs::validate_name(name)?;
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    if configs::get_config(&config_json, name).is_ok() {
+        return Err(NcmError::Other(anyhow::anyhow!("{} '{name}'", ERR_CONFIGS_EXISTS)));
+    }
+
+    let destination = settings.ncm_paths.config.join(name);
+    if destination.exists() {
+        return Err(NcmError::invalid_path(ERR_GIT_DESTINATION, destination));
+    }
+    std::fs::create_dir_all(&settings.ncm_paths.config)?;
+
+    let mut sp = Spinner::with_stream(Spinners::Dots12, format!("{}: {url}", INFO_GIT_CLONING), Stream::Stderr);
+    match git::clone(url, git_ref.as_deref(), &destination) {
+        Ok(_) => sp.stop_and_persist(&green_text("✔"), INFO_GIT_CLONED.into()),
+        Err(e) => {
+            sp.stop_and_persist(&red_text("✘"), FAILED.into());
+            return Err(NcmError::Git(e.to_string()));
+        }
+    }
+
+    if !check_for_nvim(&destination) {
+        std::fs::remove_dir_all(&destination)?;
+        return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, url));
+    }
+
+    // --| Without --ref, remember the branch the remote defaulted to
+    let git_ref = match git_ref {
+        Some(r) => Some(r.to_string()),
+        None => git::current_branch(&destination).map_err(|e| NcmError::Git(e.to_string()))?,
+    };
+
+    configs::add_config(
+        &config_json,
+        ConfigData {
+            name: name.to_string(),
+            path: path_str(&destination)?.to_string(),
+            description: description.clone().or_else(|| Some(url.to_string())),
+            data_path: Some(path_str(&settings.ncm_paths.local.join(name))?.to_string()),
+            cache_path: Some(path_str(&settings.ncm_paths.cache)?.to_string()),
+            remote: Some(url.to_string()),
+            git_ref: git_ref.clone(),
+            ..Default::default()
         },
-    ).is_ok() {
-        info!("{}: {name:?} {path:?} {description:?} {data_path_str:?} ", INFO_CONFIGS_ADDED);
+    )?;
+
+    info!("{}: {name:?} {destination:?} {url} {}", INFO_CONFIGS_ADDED, git_ref.unwrap_or_default());
+    Ok(())
+}
+
+// Create a git worktree for branch of an existing config in the ncm configs
+// root and register it with its own data directory
+pub(crate) fn add_worktree_config(source: &str, branch: &str, name: &str, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    configs::validate_name(name)?;
+    git::check_ref(branch).map_err(|e| NcmError::InvalidArgs(e.to_string()))?;
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let source_cfg = configs::get_config(&config_json, source)?;
+    if configs::get_config(&config_json, name).is_ok() {
+        return Err(NcmError::Other(anyhow::anyhow!("{} '{name}'", ERR_CONFIGS_EXISTS)));
+    }
+
+    let repo = PathBuf::from(&source_cfg.path);
+    if !git::is_repository(&repo) {
+        return Err(NcmError::invalid_path(ERR_WORKTREE_NOT_GIT, repo));
+    }
+
+    let destination = settings.ncm_paths.config.join(name);
+    if destination.exists() {
+        return Err(NcmError::invalid_path(ERR_GIT_DESTINATION, destination));
+    }
+    std::fs::create_dir_all(&settings.ncm_paths.config)?;
+
+    git::add_worktree(&repo, branch, &destination).map_err(|e| NcmError::Git(e.to_string()))?;
+    info!("{} '{branch}': {:?}", INFO_WORKTREE_CREATED, destination);
+
+    configs::add_config(
+        &config_json,
+        ConfigData {
+            name: name.to_string(),
+            path: path_str(&destination)?.to_string(),
+            description: Some(format!("{source} @ {branch}")),
+            data_path: Some(path_str(&settings.ncm_paths.local.join(name))?.to_string()),
+            cache_path: Some(path_str(&settings.ncm_paths.cache)?.to_string()),
+            remote: source_cfg.remote,
+            git_ref: Some(branch.to_string()),
+            worktree_of: Some(source.to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    info!("{}: {name:?} {destination:?}", INFO_CONFIGS_ADDED);
+    Ok(())
+}
+
+// --| New ---------------------------------
+// --|--------------------------------------
+// Scaffold a new config from a built in or user template and register it
+// with its own data directory
+pub(crate) fn new_config(name: &str, template: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    configs::validate_name(name)?;
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    if configs::get_config(&config_json, name).is_ok() {
+        return Err(NcmError::Other(anyhow::anyhow!("{} '{name}'", ERR_CONFIGS_EXISTS)));
+    }
+
+    let template_name = match template {
+        Some(t) => t.to_string(),
+        None => select_template(settings)?,
+    };
+
+    let (destination, description) = scaffold(name, &template_name, settings)?;
+
+    let data_path = Some(path_str(&settings.ncm_paths.local)?.to_string());
+    let cache_path = Some(path_str(&settings.ncm_paths.cache)?.to_string());
+    add_config(name, &destination, &Some(description), data_path, cache_path, &config_json)?;
+
+    info!("{} 'ncm load {name}'", INFO_TEMPLATE_NEXT);
+    Ok(())
+}
+
+// Write template_name into <ncm configs>/<name>, returning the path and template description.
+// The name is checked here as well since setup scaffolds without going through new_config.
+fn scaffold(name: &str, template_name: &str, settings: &Settings) -> Result<(PathBuf, String)> {
+    configs::validate_name(name)?;
+    let template = templates::find(template_name, &settings.ncm_cfg_path.join(TEMPLATES))
+        .ok_or_else(|| NcmError::NotFound(format!("{} {template_name}", ERR_TEMPLATE_NAME)))?;
+
+    let destination = settings.ncm_paths.config.join(name);
+    templates::write_template(&template, &destination)?;
+
+    info!("{} '{}': {:?}", INFO_TEMPLATE_CREATED, template.name(), destination);
+    Ok((destination, template.description()))
+}
+
+fn select_template(settings: &Settings) -> Result<String> {
+    prompt::require_terminal(INFO_TEMPLATE_SELECT)?;
+
+    let available = templates::available(&settings.ncm_cfg_path.join(TEMPLATES));
+    let options: Vec<String> = available.iter().map(|t| format!("{}  {}", t.name(), t.description())).collect();
+    let selected = Select::new(INFO_TEMPLATE_SELECT, options).raw_prompt()?;
+    Ok(available[selected.index].name().to_string())
+}
+
+// --| Try ---------------------------------
+// --|--------------------------------------
+// Register a temporary config with its own isolated directories, launch nvim
+// with it, and afterwards keep (promote) or delete it
+pub(crate) fn try_config(source: &str, name: &Option<String>, ttl: &Option<Duration>, yes: bool, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let name = match name {
+        Some(n) => n.to_string(),
+        None => trial::default_name(&configs::list_configs(&config_json)?, Utc::now()),
+    };
+    configs::validate_name(&name)?;
+    if configs::get_config(&config_json, &name).is_ok() {
+        return Err(NcmError::Other(anyhow::anyhow!("{} '{name}'", ERR_CONFIGS_EXISTS)));
+    }
+    let expires_at = trial::expires_at(ttl, Utc::now())?;
+
+    let local = PathBuf::from(source);
+    let (path, remote) = if local.is_dir() {
+        if !check_for_nvim(&local) {
+            return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, local));
+        }
+        (local.canonicalize()?, None)
+    } else if trial::is_git_url(source) {
+        let destination = settings.ncm_paths.config.join(&name);
+        if destination.exists() {
+            return Err(NcmError::invalid_path(ERR_GIT_DESTINATION, destination));
+        }
+        std::fs::create_dir_all(&settings.ncm_paths.config)?;
+
+        let mut sp = Spinner::with_stream(Spinners::Dots12, format!("{}: {source}", INFO_GIT_CLONING), Stream::Stderr);
+        if let Err(e) = git::clone(source, None, &destination) {
+            sp.stop_and_persist(&red_text("✘"), FAILED.into());
+            return Err(NcmError::Git(e.to_string()));
+        }
+        sp.stop_and_persist(&green_text("✔"), INFO_GIT_CLONED.into());
+
+        if !check_for_nvim(&destination) {
+            std::fs::remove_dir_all(&destination)?;
+            return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, source));
+        }
+        (destination, Some(source.to_string()))
+    } else {
+        return Err(NcmError::invalid_path(ERR_TRY_SOURCE, source));
+    };
+
+    let cfg = ConfigData {
+        name: name.to_string(),
+        path: path_str(&path)?.to_string(),
+        description: Some(format!("{} {source}", INFO_TRY_DESCRIPTION)),
+        data_path: Some(path_str(&settings.ncm_paths.local.join(&name))?.to_string()),
+        cache_path: Some(path_str(&settings.ncm_paths.cache)?.to_string()),
+        remote,
+        temporary: true,
+        expires_at,
+        ..Default::default()
+    };
+    configs::add_config(&config_json, cfg.clone())?;
+
+    info!("{} '{name}'", INFO_TRY_LAUNCH);
+    if let Err(e) = launch::run(&cfg, settings, &[]) {
+        trial::delete(&cfg, settings, None)?;
+        return Err(e.into());
+    }
+
+    // --| Without a terminal to ask, a trial with a ttl is left for gc
+    let choice = if yes {
+        INFO_TRY_KEEP
+    } else if prompt::require_terminal(INFO_TRY_SELECT).is_err() {
+        if ttl.is_some() { INFO_TRY_LEAVE } else { INFO_TRY_DELETE }
+    } else {
+        let mut choices = vec![INFO_TRY_KEEP, INFO_TRY_DELETE];
+        if ttl.is_some() { choices.push(INFO_TRY_LEAVE); }
+        Select::new(INFO_TRY_SELECT, choices).prompt()?
+    };
+
+    match choice {
+        INFO_TRY_KEEP => {
+            let mut kept = configs::get_config(&config_json, &name)?;
+            kept.temporary = false;
+            kept.expires_at = None;
+            configs::update_config(&config_json, kept)?;
+            info!("{} '{name}'", INFO_TRY_PROMOTED);
+        }
+        INFO_TRY_DELETE => {
+            trial::delete(&cfg, settings, None)?;
+            info!("{} '{name}'", INFO_TRY_DELETED);
+        }
+        _ => {
+            let expires_at = cfg.expires_at.map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string());
+            info!("{} {}", INFO_TRY_LEFT, expires_at.unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+// --| Repro -------------------------------
+// --|--------------------------------------
+pub(crate) fn repro_config(name: &str, plugins: &[String], run: bool, ttl: &Option<Duration>, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    configs::validate_name(name)?;
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    if configs::get_config(&config_json, name).is_ok() {
+        return Err(NcmError::Other(anyhow::anyhow!("{} '{name}'", ERR_CONFIGS_EXISTS)));
+    }
+    let expires_at = trial::expires_at(ttl, Utc::now())?;
+
+    let destination = settings.ncm_paths.config.join(name);
+    let file = repro::write(plugins, &destination)?;
+    info!("{}: {:?}", INFO_REPRO_CREATED, file);
+
+    // --| Data, state and cache live in the .repro root next to repro.lua
+    let root = destination.join(REPRO_ROOT);
+    let cfg = ConfigData {
+        name: name.to_string(),
+        path: path_str(&destination)?.to_string(),
+        description: Some(format!("{} {}", INFO_REPRO_DESCRIPTION, plugins.join(", "))),
+        data_path: Some(path_str(&root.join(XDG_DATA))?.to_string()),
+        cache_path: Some(path_str(&root.join(XDG_CACHE))?.to_string()),
+        entry: Some(REPRO_LUA.to_string()),
+        temporary: true,
+        expires_at,
+        ..Default::default()
+    };
+    configs::add_config(&config_json, cfg)?;
+
+    if run {
+        let args = ["-u".to_string(), path_str(&file)?.to_string()];
+        launch::isolated_command(&root, &args).status()
+            .map_err(|e| anyhow::anyhow!("{}: {e}", ERR_NVIM_RUN))?;
+    } else {
+        info!("{}:", INFO_REPRO_COMMAND);
+        println!("{}", repro::command_line(&file));
+    }
+    info!("{} 'ncm remove {name} --purge --include-config'", INFO_REPRO_REMOVE);
+    Ok(())
+}
+
+// --| Update ------------------------------
+// --|--------------------------------------
+pub(crate) fn update_configs(name: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let cfgs = configs::list_configs(path_str(&settings.configs_path)?)?;
+
+    let targets: Vec<&ConfigData> = match name {
+        Some(n) => {
+            let cfg = cfgs.configs.iter().find(|c| &c.name == n).ok_or_else(|| NcmError::ConfigNotFound(n.to_string()))?;
+            if cfg.remote.is_none() {
+                return Err(NcmError::Git(format!("'{n}' {}", ERR_UPDATE_NOT_GIT)));
+            }
+            vec![cfg]
+        }
+        None => cfgs.configs.iter().filter(|c| c.remote.is_some()).collect(),
+    };
+
+    if targets.is_empty() {
+        info!("{}", INFO_UPDATE_NONE);
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for cfg in targets {
+        if let Some(pinned) = &cfg.pinned {
+            info!("{} {}: {} {}", yellow_text("!"), cfg.name, INFO_UPDATE_PINNED, git::short(pinned));
+            continue;
+        }
+
+        let mut sp = Spinner::with_stream(Spinners::Dots12, cfg.name.to_string(), Stream::Stderr);
+
+        match git::update(Path::new(&cfg.path)) {
+            Ok(git::UpdateOutcome::UpToDate) => sp.stop_and_persist(&green_text("✔"), format!("{}: {}", cfg.name, INFO_UPDATE_CURRENT)),
+            Ok(git::UpdateOutcome::Skipped(reason)) => sp.stop_and_persist(&yellow_text("!"), format!("{}: {reason}", cfg.name)),
+            Ok(git::UpdateOutcome::Updated { commits, lockfiles }) => {
+                sp.stop_and_persist(&green_text("✔"), format!("{}: {} {}", cfg.name, commits.len(), INFO_UPDATE_COMMITS));
+                for commit in &commits {
+                    info!("    {commit}");
+                }
+                for lockfile in &lockfiles {
+                    warn!("{}: {}", INFO_UPDATE_LOCKFILES, Path::new(&cfg.path).join(lockfile).display());
+                }
+            }
+            Err(e) => {
+                sp.stop_and_persist(&red_text("✘"), format!("{}: {}", cfg.name, FAILED));
+                error!("{e}");
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(NcmError::Git(format!("{failed} {}", ERR_UPDATE_FAILED)));
+    }
+    Ok(())
+}
+
+// A single file is registered as its directory plus the file launched with -u
+fn split_entry(path: &Path) -> Result<(PathBuf, Option<String>)> {
+    if !path.is_file() {
+        return Ok((path.to_path_buf(), None));
+    }
+
+    let file = path.canonicalize()?;
+    if !file.extension().is_some_and(|ext| ENTRY_EXTENSIONS.iter().any(|e| ext == *e)) {
+        return Err(NcmError::invalid_path(ERR_ENTRY_EXTENSION, file));
+    }
+    let entry = file.file_name().map(|f| f.to_string_lossy().to_string());
+    Ok((file.parent().map(Path::to_path_buf).unwrap_or_default(), entry))
+}
+
+// --| Set ---------------------------------
+// --|--------------------------------------
+pub(crate) fn set_config(name: &str, description: &Option<String>, path: &Option<PathBuf>, data_path: &Option<PathBuf>, interactive: bool, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let mut cfg = configs::get_config(&config_json, name)?;
+    let original = cfg.clone();
+
+    let mut new_description = description.clone();
+    let mut new_path = path.clone();
+    let mut new_data_path = data_path.clone();
+
+    // --| Without any values to set, fall back to prompting for them
+    if interactive || (description.is_none() && path.is_none() && data_path.is_none()) {
+        prompt::require_terminal(INFO_SET_DESCRIPTION)?;
+        let current_desc = cfg.description.clone().unwrap_or_default();
+        let current_data = cfg.data_path.clone().unwrap_or_default();
+        let current_path = cfg.entry_file().map(|f| f.to_string_lossy().to_string()).unwrap_or(cfg.path.to_string());
+
+        new_description = Some(Text::new(INFO_SET_DESCRIPTION).with_initial_value(&current_desc).prompt()?);
+        new_path = Some(PathBuf::from(Text::new(INFO_SET_PATH).with_initial_value(&current_path).prompt()?));
+        new_data_path = Some(PathBuf::from(Text::new(INFO_SET_DATA_PATH).with_initial_value(&current_data).prompt()?));
+    }
+
+    // --| Like the description, an empty path keeps the current one (or none)
+    let new_path = new_path.filter(|p| !p.as_os_str().to_string_lossy().trim().is_empty());
+    let new_data_path = new_data_path.filter(|p| !p.as_os_str().to_string_lossy().trim().is_empty());
+
+    if let Some(desc) = new_description {
+        cfg.description = if desc.trim().is_empty() { None } else { Some(desc) };
+    }
+
+    // --| A .lua or .vim file makes it a single-file config, a directory needs an init file
+    if let Some(path) = new_path {
+        let (dir, entry) = split_entry(&path)?;
+        if entry.is_none() && !check_for_nvim(&dir) {
+            return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, path));
+        }
+        cfg.path = path_str(&dir)?.to_string();
+        cfg.entry = entry;
+    }
+
+    if let Some(data_path) = new_data_path {
+        if data_path.exists() && !data_path.is_dir() {
+            return Err(NcmError::invalid_path(ERR_DIR_DATA_VERIFICATION, data_path));
+        }
+
+        if !data_path.exists() {
+            info!("{}: {}", INFO_DIR_DATA, data_path.display());
+            std::fs::create_dir_all(&data_path)?;
+        }
+        cfg.data_path = Some(path_str(&data_path)?.to_string());
+    }
+
+    configs::update_config(&config_json, cfg.clone())?;
+    info!("{}: {:?} {:?} {:?} {:?}", INFO_CONFIGS_UPDATED, cfg.name, cfg.path, cfg.description, cfg.data_path);
+
+    // --| Symlinks still point at the old locations until reloaded
+    let cfgs = configs::list_configs(&config_json)?;
+    if cfgs.configs_default == name && (cfg.path != original.path || cfg.data_path != original.data_path) {
+        warn!("{} '{name}'", INFO_SET_RELOAD);
+    }
+
+    Ok(())
+}
+
+// --| Load --------------------------------
+// --|--------------------------------------
+pub(crate) fn load_config(name: &Option<String>, tag: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let nvim_path = settings.nvim_path.clone();
+    let nvim_data = settings.data_path.clone();
+    let xdg_config_set = settings.xdg_config_is_set;
+    let xdg_data_set = settings.xdg_data_is_set;
+
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let name_str = match name {
+        Some(n) if n == PREVIOUS_CONFIG => {
+            let cfgs = configs::list_configs(&config_json)?;
+            history::previous_config(&settings.history_path, &cfgs.configs_default)?
+        }
+        Some(n) => n.to_string(),
+        None => {
+            let cfgs = configs::list_configs(&config_json)?;
+            select_config(INFO_LOAD_SELECT, &cfgs, tag, false)?
+        }
+    };
+
+    // --| Refuse single-file configs, and drifted ones with strict pins, before the default is changed
+    let selected = configs::get_config(&config_json, &name_str)?;
+    if let Some(file) = selected.entry_file() {
+        return Err(NcmError::invalid_path(ERR_ENTRY_LOAD, file));
+    }
+    check_pin(&selected, settings)?;
+
+    let cfg = configs::load_configs(&config_json, &name_str)?;
+    info!("{}: {:?}", INFO_CONFIGS_LOADING, cfg.name);
+
+    let config_buf = PathBuf::from(&cfg.path);
+    let data_buf = PathBuf::from(cfg.data_path.unwrap_or_default());
+
+    verify_config_directory(&nvim_path, &config_buf, xdg_config_set)?;
+    debug!("{}: {:?} - {}: {:?}", "System Config Path: ", nvim_path, "Config Path: ", config_buf);
+    configs::create_symlink(nvim_path, config_buf)?;
+
+    verify_data_directory(&nvim_data, &data_buf, &name_str, xdg_data_set)?;
+    debug!("{}: {:?} - {}: {:?}", "System Data Path:   ", nvim_data, "Data Path:   ", data_buf);
+    configs::create_symlink(nvim_data, data_buf)?;
+
+    // --| Not handling cache on Windows ---
+    // if verify_cache_path(&nvim_cache, &cache_buf, &name_str).is_ok() {
+    //     debug!("{}: {:?} - {}: {}", "System Cache Path:  ", nvim_cache, "Cache Path: ", cache_str);
+    //     configs::create_symlink(nvim_cache, cache_buf).expect(ERR_SYMLINK_CREATE);
+    // } else {
+    //     error!("{}: {:?} - {}: {}", "System Cache Path:  ", nvim_cache, "Cache Path: ", cache_str);
+    // }
+
+    if let Err(e) = configs::record_load(&config_json, &name_str) {
+        warn!("{}: {e}", ERR_CONFIGS_USAGE);
+    }
+
+    if let Err(e) = history::record_switch(&settings.history_path, &name_str) {
+        warn!("{e}");
+    }
+
+    Ok(())
+}
+
+// Warn when a pinned config is not at its pinned commit, or refuse with strict_pins = true
+fn check_pin(cfg: &ConfigData, settings: &Settings) -> Result<()> {
+    let Some(pinned) = &cfg.pinned else { return Ok(()) };
+
+    let head = git::head(Path::new(&cfg.path)).unwrap_or_default();
+    if &head == pinned { return Ok(()); }
+
+    let message = format!("'{}' {} {} ({} {})", cfg.name, ERR_PIN_DRIFT, git::short(pinned), INFO_PIN_AT, git::short(&head));
+    let strict = settings.settings.getbool(NCM, STRICT_PINS).ok().flatten().unwrap_or(false);
+
+    if strict {
+        return Err(NcmError::Git(format!("{message}, {}", HINT_PIN)));
+    }
+    warn!("{message}");
+    Ok(())
+}
+
+// --| Run ---------------------------------
+// --|--------------------------------------
+// Single-file configs are passed with -u, directories are linked into the
+// isolated root. The nvim symlinks and the default are left alone.
+pub(crate) fn run_config(name: &str, args: &[String], settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let cfg = configs::get_config(&config_json, name)?;
+
+    match cfg.entry_file() {
+        Some(file) if !file.is_file() => return Err(NcmError::invalid_path(ERR_CONFIGS_PATH, file)),
+        None if !check_for_nvim(Path::new(&cfg.path)) => return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, &cfg.path)),
+        _ => {}
+    }
+    check_pin(&cfg, settings)?;
+
+    info!("{} '{name}'", INFO_RUN_LAUNCH);
+    launch::run(&cfg, settings, args)?;
+
+    if let Err(e) = configs::record_load(&config_json, name) {
+        warn!("{}: {e}", ERR_CONFIGS_USAGE);
+    }
+    Ok(())
+}
+
+// --| Pin ---------------------------------
+// --|--------------------------------------
+pub(crate) fn pin_config(name: &str, rev: &Option<String>, config_json: &str) -> Result<()> {
+    let mut cfg = configs::get_config(config_json, name)?;
+    let rev = rev.as_deref().unwrap_or("HEAD");
+
+    let commit = git::resolve(Path::new(&cfg.path), rev).map_err(|e| NcmError::Git(e.to_string()))?;
+    info!("{} '{name}': {} ({rev})", INFO_PINNED, git::short(&commit));
+
+    cfg.pinned = Some(commit);
+    configs::update_config(config_json, cfg)?;
+    Ok(())
+}
+
+pub(crate) fn unpin_config(name: &str, config_json: &str) -> Result<()> {
+    let mut cfg = configs::get_config(config_json, name)?;
+    if cfg.pinned.take().is_none() {
+        return Err(NcmError::NotFound(format!("'{name}' {}", ERR_NOT_PINNED)));
+    }
+
+    configs::update_config(config_json, cfg)?;
+    info!("{} '{name}'", INFO_UNPINNED);
+    Ok(())
+}
+
+// --| Remove ------------------------------
+// --|--------------------------------------
+pub(crate) fn remove_config(name: &Option<String>, purge: bool, include_config: bool, trash: bool, yes: bool, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let config_json = config_json.as_str();
+
+    let name_str = match name {
+        Some(n) => n.to_string(),
+        None => {
+            let cfgs = configs::list_configs(config_json)?;
+            let selected = select_config(INFO_REMOVE_SELECT, &cfgs, &None, false)?;
+
+            let confirmed = prompt::confirm(Confirm::new(&format!("{} '{selected}'?", INFO_REMOVE_CONFIRM))
+                .with_default(false), yes)?;
+
+            if !confirmed {
+                info!("{}", INFO_REMOVE_CANCELLED);
+                return Ok(());
+            }
+            selected
+        }
+    };
+
+    // --| Worktrees go first, git refuses to remove one with local changes
+    let cfg = configs::get_config(config_json, &name_str)?;
+    let worktree = if cfg.worktree_of.is_some() {
+        Some(git::common_dir(Path::new(&cfg.path)).map_err(|e| NcmError::Git(e.to_string()))?)
+    } else { None };
+
+    if !purge {
+        if let Some(common_dir) = &worktree {
+            remove_worktree(common_dir, &cfg)?;
+        }
+        configs::remove_config(&name_str, config_json)?;
+        info!("{}: {name_str:?}", INFO_CONFIGS_REMOVED);
+        return Ok(());
+    }
+
+    // --| Purge ---------------------------
+    let cfgs = configs::list_configs(config_json)?;
+
+    if is_loaded(&cfg, &cfgs, settings) {
+        return Err(NcmError::ConfigInUse(name_str));
+    }
+
+    // --| The worktree directory is removed through git
+    let mut targets = cleanup::purge_targets(&cfg, settings, include_config);
+    if worktree.is_some() {
+        targets.retain(|t| t.path != Path::new(&cfg.path));
+    }
+    let total: u64 = targets.iter().map(|t| t.size).sum();
+
+    info!("{} '{name_str}':", if trash { INFO_PURGE_TRASH } else { INFO_PURGE_DELETE });
+    for target in &targets {
+        info!("  {:>10}  {}", cleanup::format_size(target.size), target.path.display());
+    }
+    info!("  {:>10}  {}", cleanup::format_size(total), INFO_PURGE_TOTAL);
+
+    let confirmed = prompt::confirm(Confirm::new(&format!("{} '{name_str}'?", INFO_PURGE_CONFIRM))
+        .with_default(false), yes)?;
+
+    if !confirmed {
+        info!("{}", INFO_REMOVE_CANCELLED);
+        return Ok(());
+    }
+
+    if let Some(common_dir) = &worktree {
+        remove_worktree(common_dir, &cfg)?;
+    }
+    configs::remove_config(&name_str, config_json)?;
+    info!("{}: {name_str:?}", INFO_CONFIGS_REMOVED);
+
+    let trash_dir = settings.ncm_cfg_path.join(TRASH);
+    for target in &targets {
+        match cleanup::remove_target(&target.path, if trash { Some(trash_dir.as_path()) } else { None }) {
+            Ok(_) => debug!("{}: {:?}", INFO_PURGE_REMOVED, target.path),
+            Err(e) => error!("{}: {:?} {e}", ERR_CLEANUP_REMOVE, target.path),
+        }
+    }
+
+    let purge_complete = RGB(146, 181, 95).paint(INFO_PURGE_COMPLETE);
+    info!("{}", purge_complete);
+    Ok(())
+}
+
+fn remove_worktree(common_dir: &Path, cfg: &ConfigData) -> Result<()> {
+    git::remove_worktree(common_dir, Path::new(&cfg.path)).map_err(|e| NcmError::Git(e.to_string()))?;
+    info!("{}: {}", INFO_WORKTREE_REMOVED, cfg.path);
+    Ok(())
+}
+
+// A config counts as loaded when it is the stored default,
+// or when the nvim config or data symlink currently points into it
+fn is_loaded(cfg: &ConfigData, cfgs: &Configs, settings: &Settings) -> bool {
+    if cfgs.configs_default == cfg.name { return true; }
+
+    let points_to = |link: &Path, target: &str| -> bool {
+        match (link.canonicalize(), Path::new(target).canonicalize()) {
+            (Ok(a), Ok(b)) => link.is_symlink() && a == b,
+            _ => false,
+        }
+    };
+
+    points_to(&settings.nvim_path, &cfg.path) || cfg.data_path.as_ref().is_some_and(|d| points_to(&settings.data_path, d))
+}
+
+// --| Garbage Collect ---------------------
+// --|--------------------------------------
+pub(crate) fn collect_garbage(delete: bool, trash: bool, yes: bool, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let cfgs = configs::list_configs(path_str(&settings.configs_path)?)?;
+    let orphans = cleanup::find_orphans(&cfgs, settings);
+    let (loaded, expired): (Vec<ConfigData>, Vec<ConfigData>) = trial::expired(&cfgs, Utc::now()).into_iter()
+        .partition(|cfg| is_loaded(cfg, &cfgs, settings));
+
+    // --| Same as remove --purge, a loaded config is never deleted
+    for cfg in &loaded {
+        warn!("{} '{}'", INFO_GC_SKIP_LOADED, cfg.name);
+    }
+
+    if orphans.is_empty() && expired.is_empty() {
+        info!("{}", INFO_GC_NONE);
+        return Ok(());
+    }
+
+    if !orphans.is_empty() {
+        let total: u64 = orphans.iter().map(|o| o.size).sum();
+        info!("{}:", INFO_GC_FOUND);
+        for orphan in &orphans {
+            info!("  {:>10}  {}", cleanup::format_size(orphan.size), orphan.path.display());
+        }
+        info!("  {:>10}  {}", cleanup::format_size(total), INFO_PURGE_TOTAL);
+    }
+
+    if !expired.is_empty() {
+        info!("{}:", INFO_GC_EXPIRED);
+        for cfg in &expired {
+            info!("  {}  {}", cfg.name, format_timestamp(&cfg.expires_at));
+        }
+    }
+
+    if !delete {
+        info!("{}", INFO_GC_DRY_RUN);
+        return Ok(());
+    }
+
+    let confirmed = prompt::confirm(Confirm::new(if trash { INFO_GC_CONFIRM_TRASH } else { INFO_GC_CONFIRM })
+        .with_default(false), yes)?;
+
+    if !confirmed {
+        info!("{}", INFO_GC_CANCELLED);
+        return Ok(());
+    }
+
+    let trash_dir = settings.ncm_cfg_path.join(TRASH);
+    for orphan in &orphans {
+        match cleanup::remove_target(&orphan.path, if trash { Some(trash_dir.as_path()) } else { None }) {
+            Ok(_) => info!("{}: {:?}", INFO_PURGE_REMOVED, orphan.path),
+            Err(e) => error!("{}: {:?} {e}", ERR_CLEANUP_REMOVE, orphan.path),
+        }
+    }
+
+    for cfg in &expired {
+        match trial::delete(cfg, settings, if trash { Some(trash_dir.as_path()) } else { None }) {
+            Ok(_) => info!("{} '{}'", INFO_TRY_DELETED, cfg.name),
+            Err(e) => error!("{}: {:?} {e}", ERR_CLEANUP_REMOVE, cfg.name),
+        }
+    }
+
+    Ok(())
+}
+
+// --| Doctor ------------------------------
+// --|--------------------------------------
+// Print the result of each check, fails if any of them failed
+pub(crate) fn run_doctor(format: OutputFormat, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let report = DoctorReport::new(doctor::run_checks(settings));
+
+    if format.is_structured() {
+        output::emit(format, OUTPUT_KIND_DOCTOR, &report)?;
+    } else {
+        print_doctor_report(&report);
+    }
+
+    match report.failed {
+        0 => Ok(()),
+        failed => Err(NcmError::ChecksFailed(failed)),
+    }
+}
+
+fn print_doctor_report(report: &DoctorReport) {
+    for result in &report.checks {
+        let symbol = match result.status {
+            CheckStatus::Pass => green_text(CHECK),
+            CheckStatus::Warn => yellow_text(DOCTOR_WARN),
+            CheckStatus::Fail => red_text(CROSS),
+        };
+
+        println!("{} {}: {}", symbol, result.name, result.message);
+        if let Some(hint) = &result.hint {
+            println!("    {}", hint);
+        }
+    }
+
+    println!("{}", CLI_SPACER);
+    println!("{} {}: {} {}, {} {}, {} {}", report.checks.len(), DOCTOR_SUMMARY,
+             green_text(&report.passed.to_string()), DOCTOR_PASSED,
+             yellow_text(&report.warnings.to_string()), DOCTOR_WARNINGS,
+             red_text(&report.failed.to_string()), DOCTOR_FAILED);
+}
+
+// --| Status ------------------------------
+// --|--------------------------------------
+pub(crate) fn show_status(short: bool, format: OutputFormat, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let cfgs = configs::list_configs(path_str(&settings.configs_path)?)?;
+    let status = status::resolve_status(settings, &cfgs);
+
+    if format.is_structured() {
+        return Ok(output::emit(format, OUTPUT_KIND_STATUS, &status)?);
+    }
+
+    if short {
+        let drift = if status.drift { STATUS_DRIFT_MARKER } else { "" };
+        println!("{}{}", status.current.as_deref().unwrap_or(STATUS_UNKNOWN), drift);
+        return Ok(());
+    }
+
+    let status_str = RGB(70, 130, 180).paint(CLI_STATUS);
+    println!("{}", status_str);
+    println!("{}", CLI_SPACER);
+
+    let kind_str = RGB(70, 130, 180).paint(CLI_TABLE_KIND);
+    let path_str = RGB(70, 130, 180).paint(CLI_TABLE_PATH);
+    let target_str = RGB(70, 130, 180).paint(CLI_TABLE_TARGET);
+    let config_str = RGB(70, 130, 180).paint(CLI_TABLE_CONFIG);
+
+    let mut table = Table::new();
+    table.set_titles(row![b->kind_str, b->path_str, b->target_str, b->config_str]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+
+    for link in &status.links {
+        let target = link.target.as_ref().map(|t| t.display().to_string()).unwrap_or("-".to_string());
+        let owner = match link.state {
+            LinkState::Linked => link.owner.clone().unwrap_or_default(),
+            LinkState::Unregistered => STATUS_UNREGISTERED.to_string(),
+            LinkState::Dangling => STATUS_DANGLING.to_string(),
+            LinkState::Unmanaged => STATUS_UNMANAGED.to_string(),
+            LinkState::Missing => STATUS_MISSING.to_string(),
+        };
+        table.add_row(row![link.kind.label(), link.path.display(), target, owner]);
+    }
+
+    let summary = if status.drift {
+        Cell::new_align(&format!("{} '{}'", STATUS_DRIFT, status.default), Alignment::LEFT)
+            .with_style(Attr::Bold)
+            .with_style(Attr::ForegroundColor(color::RED))
+    } else {
+        Cell::new_align(&format!("{}: {}", DEFAULT_CURRENT, status.default), Alignment::LEFT)
+            .with_style(Attr::Bold)
+            .with_style(Attr::ForegroundColor(color::GREEN))
+    };
+    table.add_row(Row::new(vec![summary.with_hspan(4)]));
+
+    table.printstd();
+    Ok(())
+}
+
+// --| Repair ------------------------------
+// --|--------------------------------------
+pub(crate) fn run_repair(yes: bool, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let mut repaired = 0;
+
+    // --| settings.ini ----------------------
+    if let Some(action) = repair::repair_settings(settings)? {
+        info!("{} {}", green_text(CHECK), action);
+        repaired += 1;
+    }
+
+    // --| Data directories ------------------
+    let cfgs = configs::list_configs(&config_json)?;
+    for path in repair::repair_data_dirs(&cfgs)? {
+        info!("{} {}: {:?}", green_text(CHECK), REPAIR_DATA_DIR, path);
+        repaired += 1;
+    }
+
+    // --| Registry entries without a config directory
+    for cfg in repair::missing_configs(&cfgs) {
+        warn!("{} '{}': {:?}", REPAIR_MISSING_CONFIG, cfg.name, cfg.path);
+
+        let confirmed = prompt::confirm(Confirm::new(&format!("{} '{}'?", INFO_REMOVE_CONFIRM, cfg.name))
+            .with_default(false), yes)?;
+
+        if confirmed {
+            configs::remove_config(&cfg.name, &config_json)?;
+            info!("{} {}: {:?}", green_text(CHECK), INFO_CONFIGS_REMOVED, cfg.name);
+            repaired += 1;
+        }
+    }
+
+    // --| Symlinks --------------------------
+    let cfgs = configs::list_configs(&config_json)?;
+    match repair::repair_symlinks(settings, &cfgs) {
+        Ok(result) => {
+            for link in result.repaired {
+                info!("{} {}: {}", green_text(CHECK), REPAIR_SYMLINK, link);
+                repaired += 1;
+            }
+            for path in result.refused {
+                warn!("{}: {}: {:?}", ERR_REPAIR_SYMLINK, DOCTOR_LINK_REAL_DIR, path);
+            }
+        }
+        Err(e) => warn!("{}: {e}", ERR_REPAIR_SYMLINK),
+    }
+
+    if repaired == 0 {
+        info!("{}", REPAIR_NOTHING);
     } else {
-        error!("{}: {name:?} {path:?} {description:?}", ERR_CONFIGS_ADD);
+        let repair_complete = RGB(146, 181, 95).paint(REPAIR_COMPLETE);
+        info!("{}", repair_complete);
     }
+
+    Ok(())
 }
 
-// --| Load --------------------------------
+// --| Teardown ----------------------------
 // --|--------------------------------------
-pub(crate) fn load_config(name: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) {
-    let nvim_path = PathBuf::from(settings.nvim_path.to_str().unwrap());
-    let nvim_data = settings.data_path.clone();
-    let _nvim_cache = settings.cache_path.clone();
-    let name_str = name.to_owned().unwrap();
-    let xdg_config_set = settings.xdg_config_is_set;
-    let xdg_data_set = settings.xdg_data_is_set;
-
-    let config_json = settings.configs_path.to_str().unwrap();
-    let cfg = configs::load_configs(config_json, &name.clone().unwrap()).expect(ERR_CONFIGS_LOAD);
-    info!("{}: {:?}", INFO_CONFIGS_LOADING, cfg.name);
-
-    let config_path = PathBuf::from_str(&cfg.path).ok();
-    let data_path = PathBuf::from_str(&cfg.data_path.unwrap()).ok();
-    let cache_path = PathBuf::from_str(&cfg.cache_path.unwrap()).ok();
+pub(crate) fn run_teardown(name: &Option<String>, archive: &Option<PathBuf>, discard_others: bool, yes: bool, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let cfgs = configs::list_configs(path_str(&settings.configs_path)?)?;
 
-    let config_buf = config_path.unwrap();
-    let cfg_str = config_buf.to_str().unwrap();
+    let name_str = match name {
+        Some(n) => n.to_string(),
+        None if !cfgs.configs_default.is_empty() => cfgs.configs_default.to_string(),
+        None => select_config(INFO_TEARDOWN_SELECT, &cfgs, &None, false)?,
+    };
 
-    let data_buf = data_path.unwrap();
-    let data_str = data_buf.to_str().unwrap();
+    let plan = teardown::plan(settings, &cfgs, &name_str, archive.is_some(), discard_others)?;
 
-    let cache_buf = cache_path.unwrap();
-    let _cache_str = cache_buf.to_str().unwrap();
+    info!("{} '{}': {}", INFO_TEARDOWN_RESTORE, plan.config.name, plan.config.path);
+    if let Some(archive_dir) = archive {
+        for cfg in &plan.archive {
+            info!("  {}  {:?}", INFO_TEARDOWN_ARCHIVE, archive_dir.join(format!("{}.{}", cfg.name, ZIP)));
+        }
+    }
+    for cfg in &plan.discard {
+        for path in teardown::discarded_paths(cfg, settings) {
+            info!("  {}  '{}': {}", INFO_TEARDOWN_DELETE, cfg.name, path.display());
+        }
+    }
+    for path in &plan.remove {
+        info!("  {}  {}", INFO_TEARDOWN_REMOVE, path.display());
+    }
 
-    if verify_config_directory(&nvim_path, &config_buf, xdg_config_set).is_ok() {
-        debug!("{}: {:?} - {}: {}", "System Config Path: ", nvim_path, "Config Path: ", cfg_str);
-        configs::create_symlink(nvim_path, config_buf).expect(ERR_SYMLINK_CREATE);
-    } else {
-        return error!("{}: {:?} - {}: {}", "System Config Path: ", nvim_path, "Config Path: ", cfg_str);
+    let confirmed = prompt::confirm(Confirm::new(INFO_TEARDOWN_CONFIRM).with_default(false), yes)?;
+    if !confirmed {
+        info!("{}", INFO_TEARDOWN_CANCELLED);
+        return Ok(());
     }
 
-    if verify_data_directory(&nvim_data, &data_buf, &name_str, xdg_data_set).is_ok() {
-        debug!("{}: {:?} - {}: {}", "System Data Path:   ", nvim_data, "Data Path:   ", data_str);
-        configs::create_symlink(nvim_data, data_buf).expect(ERR_SYMLINK_CREATE);
-    } else {
-        return error!("{}: {:?} - {}: {}", "System Data Path:   ", nvim_data, "Data Path:   ", data_str);
+    if let Some(archive_dir) = archive {
+        teardown::archive_configs(&plan.archive, archive_dir)
+            .map_err(|e| NcmError::Backup(e.to_string()))?;
     }
 
-    // --| Not handling cache on Windows ---
-    if cfg!(target_os = "windows") {}
-    // if verify_cache_path(&nvim_cache, &cache_buf, &name_str).is_ok() {
-    //     debug!("{}: {:?} - {}: {}", "System Cache Path:  ", nvim_cache, "Cache Path: ", cache_str);
-    //     configs::create_symlink(nvim_cache, cache_buf).expect(ERR_SYMLINK_CREATE);
-    // } else {
-    //     error!("{}: {:?} - {}: {}", "System Cache Path:  ", nvim_cache, "Cache Path: ", cache_str);
-    // }
+    teardown::remove_worktrees(&plan.discard)?;
+    teardown::materialize(settings, &plan.config)?;
+    teardown::remove_state(&plan.remove)?;
+
+    let teardown_complete = RGB(146, 181, 95).paint(INFO_TEARDOWN_COMPLETE);
+    info!("{} {:?}", teardown_complete, settings.nvim_path);
+    Ok(())
 }
 
 // --| Verify Original Config Directory ---------
-fn verify_config_directory(nvim_path: &Path, new_path: &PathBuf, xdg_config_set: bool) -> Result<()> {
-    if !nvim_path.ends_with(NVIM) && !nvim_path.parent().unwrap().ends_with(
-        match cfg!(target_os = "windows") {
-            true => if !xdg_config_set { NCM_DATA_WIN } else { CONFIG },
-            false => CONFIG,
-        }
-    ) { return Err(anyhow!("{}: {:?}", ERR_DIR_CONFIG_VERIFICATION, new_path))?; }
+fn verify_config_directory(nvim_path: &Path, new_path: &Path, xdg_config_set: bool) -> Result<()> {
+    let config_parent = match cfg!(target_os = "windows") {
+        true => if !xdg_config_set { NCM_DATA_WIN } else { CONFIG },
+        false => CONFIG,
+    };
+
+    if !nvim_path.ends_with(NVIM) && !nvim_path.parent().is_some_and(|p| p.ends_with(config_parent)) {
+        return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, new_path));
+    }
 
     let file_one = new_path.join(INIT_LUA);
     let file_two = new_path.join(INIT_VIM);
 
     if !file_one.exists() && !file_two.exists() {
-        return Err(anyhow!("{}: {:?}", ERR_DIR_CONFIG_VERIFICATION, new_path))?;
+        return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, new_path));
     }
     Ok(())
 }
 
 // --| Verify Original Data Directory -----------
-fn verify_data_directory(nvim_data: &PathBuf, new_path: &PathBuf, name: &str, xdg_data_set: bool) -> Result<()> {
-    if !nvim_data.exists() { return Err(anyhow!("{}: {:?}", ERR_DIR_DATA_VERIFICATION, nvim_data))?; }
-
-    if !nvim_data.ends_with(if cfg!(target_os = "windows") { NVIM_DATA } else { NVIM }) &&
-        !nvim_data.parent().unwrap().ends_with(
-            match cfg!(target_os = "windows") {
-                true => if !xdg_data_set { WIN_DATA } else { SHARE },
-                false => SHARE,
-            }
-        ) { return Err(anyhow!("{}: {:?}", ERR_DIR_DATA_VERIFICATION, nvim_data))?; }
+fn verify_data_directory(nvim_data: &Path, new_path: &Path, name: &str, xdg_data_set: bool) -> Result<()> {
+    if !nvim_data.exists() { return Err(NcmError::invalid_path(ERR_DIR_DATA_VERIFICATION, nvim_data)); }
+
+    let data_name = if cfg!(target_os = "windows") { NVIM_DATA } else { NVIM };
+    let data_parent = match cfg!(target_os = "windows") {
+        true => if !xdg_data_set { WIN_DATA } else { SHARE },
+        false => SHARE,
+    };
+
+    if !nvim_data.ends_with(data_name) && !nvim_data.parent().is_some_and(|p| p.ends_with(data_parent)) {
+        return Err(NcmError::invalid_path(ERR_DIR_DATA_VERIFICATION, nvim_data));
+    }
 
-    if !new_path.ends_with(name) && !new_path.parent().unwrap().ends_with(
-        if cfg!(target_os = "windows") && !xdg_data_set { NCM_DATA_WIN } else { NCM_DATA }
-    ) { return Err(anyhow!("{}: {:?}", ERR_DIR_DATA_VERIFICATION, new_path))?; }
+    let ncm_data = if cfg!(target_os = "windows") && !xdg_data_set { NCM_DATA_WIN } else { NCM_DATA };
+    if !new_path.ends_with(name) && !new_path.parent().is_some_and(|p| p.ends_with(ncm_data)) {
+        return Err(NcmError::invalid_path(ERR_DIR_DATA_VERIFICATION, new_path));
+    }
     Ok(())
 }
 
 // --| Verify Original Cache Path ---------------
-fn _verify_cache_path(nvim_cache: &PathBuf, new_path: &PathBuf, name: &str) -> Result<()> {
+fn _verify_cache_path(nvim_cache: &Path, new_path: &Path, name: &str) -> Result<()> {
     if !nvim_cache.exists() {
-        return Err(anyhow!("{}: {:?}", ERR_DIR_CACHE_VERIFICATION, nvim_cache))?;
+        return Err(NcmError::invalid_path(ERR_DIR_CACHE_VERIFICATION, nvim_cache));
     }
 
-    if !nvim_cache.ends_with(NVIM) && !nvim_cache.parent().unwrap().ends_with(CACHE) {
-        return Err(anyhow!("{}: {:?}", ERR_DIR_CACHE_VERIFICATION, nvim_cache))?;
+    if !nvim_cache.ends_with(NVIM) && !nvim_cache.parent().is_some_and(|p| p.ends_with(CACHE)) {
+        return Err(NcmError::invalid_path(ERR_DIR_CACHE_VERIFICATION, nvim_cache));
     }
 
-    if !new_path.ends_with(name) && !new_path.parent().unwrap().ends_with(NCM_DATA) {
-        return Err(anyhow!("{}: {:?}", ERR_DIR_DATA_VERIFICATION, new_path))?;
+    if !new_path.ends_with(name) && !new_path.parent().is_some_and(|p| p.ends_with(NCM_DATA)) {
+        return Err(NcmError::invalid_path(ERR_DIR_DATA_VERIFICATION, new_path));
     }
     Ok(())
 }
 
 // --| List --------------------------------
 // --|--------------------------------------
-pub(crate) fn list_configs(config_json: &str) {
-    let cfgs = configs::list_configs(config_json).expect(ERR_CONFIGS_LIST);
+pub(crate) fn list_configs(config_json: &str, tag: &Option<String>, sort: &Option<ListSort>, format: OutputFormat, git_cache: &Path) -> Result<()> {
+    let mut cfgs = configs::list_configs(config_json)?;
+
+    // --| Most recent first, configs never loaded/without a date last
+    match sort {
+        Some(ListSort::Name) => cfgs.configs.sort_by(|a, b| a.name.cmp(&b.name)),
+        Some(ListSort::LastUsed) => cfgs.configs.sort_by_key(|c| Reverse(c.last_loaded_at)),
+        Some(ListSort::Created) => cfgs.configs.sort_by_key(|c| Reverse(c.created_at)),
+        None => {}
+    }
+
+    if let Some(t) = tag {
+        cfgs.configs.retain(|cfg| cfg.has_tag(t));
+    }
+
+    if format.is_structured() {
+        return Ok(output::emit(format, OUTPUT_KIND_LIST, &cfgs)?);
+    }
+
     let current_default = format!("{}: {}", DEFAULT_CURRENT, cfgs.configs_default);
 
     let current_str = RGB(70, 130, 180).paint(CLI_CURRENT_CONFIGS);
@@ -199,22 +1342,99 @@ pub(crate) fn list_configs(config_json: &str) {
     let name_str = RGB(70, 130, 180).paint(CLI_TABLE_NAME);
     let path_str = RGB(70, 130, 180).paint(CLI_TABLE_PATH);
     let desc_str = RGB(70, 130, 180).paint(CLI_TABLE_DESC);
+    let tags_str = RGB(70, 130, 180).paint(CLI_TABLE_TAGS);
+    let created_str = RGB(70, 130, 180).paint(CLI_TABLE_CREATED);
+    let loaded_str = RGB(70, 130, 180).paint(CLI_TABLE_LAST_LOADED);
+    let count_str = RGB(70, 130, 180).paint(CLI_TABLE_LOADS);
+    let pin_str = RGB(70, 130, 180).paint(CLI_TABLE_PIN);
+    let branch_str = RGB(70, 130, 180).paint(CLI_TABLE_BRANCH);
+    let sync_str = RGB(70, 130, 180).paint(CLI_TABLE_SYNC);
+    let dirty_str = RGB(70, 130, 180).paint(CLI_TABLE_DIRTY);
+
+    let paths: Vec<PathBuf> = cfgs.configs.iter().map(|cfg| PathBuf::from(&cfg.path)).collect();
+    let statuses = git::statuses(&paths, git_cache);
 
     let mut table = Table::new();
-    table.set_titles(row![b->name_str, b->path_str, b->desc_str]);
+    table.set_titles(row![b->name_str, b->path_str, b->desc_str, b->tags_str, b->created_str, b->loaded_str, b->count_str,
+        b->branch_str, b->sync_str, b->dirty_str, b->pin_str]);
 
     table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    for cfg in cfgs.configs {
-        table.add_row(row![cfg.name, cfg.path, cfg.description.unwrap_or("".to_string())]);
+    for (cfg, status) in cfgs.configs.into_iter().zip(statuses) {
+        let pin = pin_status(&cfg, &status);
+        let (branch, sync, dirty) = git_columns(&status);
+        table.add_row(row![
+            cfg.name,
+            cfg.path,
+            cfg.description.unwrap_or("".to_string()),
+            cfg.tags.join(", "),
+            format_timestamp(&cfg.created_at),
+            format_timestamp(&cfg.last_loaded_at),
+            r->cfg.load_count,
+            branch,
+            sync,
+            c->dirty,
+            pin
+        ]);
     }
 
     table.add_row(Row::new(vec![Cell::new_align(
         &current_default, Alignment::LEFT)
         .with_style(Attr::Bold)
         .with_style(Attr::ForegroundColor(color::GREEN))
-        .with_hspan(3)]));
+        .with_hspan(11)]));
+
+    table.printstd();
+    Ok(())
+}
+
+// Pinned commit, green when the config is at it and red with the current commit when not
+fn pin_status(cfg: &ConfigData, status: &Option<git::GitStatus>) -> String {
+    let Some(pinned) = &cfg.pinned else { return String::new() };
+
+    match status {
+        Some(status) if &status.head == pinned => green_text(git::short(pinned)).to_string(),
+        Some(status) => red_text(&format!("{} ({} {})", git::short(pinned), INFO_PIN_AT, git::short(&status.head))).to_string(),
+        None => red_text(&format!("{} (?)", git::short(pinned))).to_string(),
+    }
+}
+
+// Branch (or short commit when detached), ahead/behind upstream and a dirty marker
+fn git_columns(status: &Option<git::GitStatus>) -> (String, String, String) {
+    let Some(status) = status else { return (String::new(), String::new(), String::new()) };
+
+    let branch = status.branch.clone().unwrap_or_else(|| format!("({})", git::short(&status.head)));
+    let sync = match status.ahead_behind {
+        Some((0, 0)) => CLI_GIT_SYNCED.to_string(),
+        Some((ahead, behind)) => yellow_text(&format!("↑{ahead} ↓{behind}")).to_string(),
+        None => String::new(),
+    };
+    let dirty = if status.dirty { red_text(CLI_GIT_DIRTY).to_string() } else { String::new() };
+
+    (branch, sync, dirty)
+}
+
+// --| History -----------------------------
+// --|--------------------------------------
+pub(crate) fn list_history(count: usize, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let history = history::read_history(&settings.history_path)?;
+
+    let history_str = RGB(70, 130, 180).paint(CLI_SWITCH_HISTORY);
+    println!("{}", history_str);
+    println!("{}", CLI_SPACER);
+
+    let loaded_str = RGB(70, 130, 180).paint(CLI_TABLE_LOADED_AT);
+    let name_str = RGB(70, 130, 180).paint(CLI_TABLE_NAME);
+
+    let mut table = Table::new();
+    table.set_titles(row![b->loaded_str, b->name_str]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+
+    for entry in history.entries.iter().rev().take(count) {
+        table.add_row(row![format_timestamp(&Some(entry.loaded_at)), entry.name]);
+    }
 
     table.printstd();
+    Ok(())
 }
 
 fn check_for_nvim(nvim_path: &Path) -> bool {
@@ -229,369 +1449,491 @@ fn check_for_nvim(nvim_path: &Path) -> bool {
 
 // --| Setup -------------------------------
 // --|--------------------------------------
-pub(crate) fn check_setup(settings: &mut RwLockWriteGuard<'_, Settings>, setup_complete: bool) -> Result<()> {
-    let mut nvim_symlinked: bool = false;
-
-    if !check_for_nvim(&settings.nvim_path) {
-        if !&settings.xdg_config_is_set {
-            if cfg!(windows) {
-                warn!("{} {} ", ERR_NVIM_NOT_FOUND_WIN, ERR_NVIM_NOT_FOUND_WIN_NO_XDG);
-            } else {
-                warn!("{} {} ", ERR_NVIM_NOT_FOUND_LINUX, ERR_NVIM_NOT_FOUND_LINUX_NO_XDG);
-            }
-        } else {
-            if cfg!(windows) {
-                warn!("{} {} ", ERR_NVIM_NOT_FOUND, ERR_NVIM_NOT_FOUND_WIN_XDG);
-            } else {
-                warn!("{}", ERR_NVIM_NOT_FOUND_LINUX);
-            }
-            return Err(anyhow!("{}: {:?}", ERR_NVIM_NOT_FOUND, settings.nvim_path));
-        }
-        return Err(anyhow!("{}: {:?}", ERR_NVIM_NOT_FOUND, settings.nvim_path));
+// Answers to the first-time setup prompts. When not interactive,
+// values that were not given fall back to the prompt defaults
+pub(crate) struct SetupOptions {
+    pub interactive: bool,
+    pub config_root: Option<PathBuf>,
+    pub name: Option<String>,
+    pub backup: bool,
+    pub resume: bool,
+    pub rollback: bool,
+    pub from: Option<PathBuf>,
+    pub template: Option<String>,
+}
+
+// Where the first configuration comes from when there is no nvim config to move
+enum FirstConfig {
+    Directory(PathBuf),
+    Template(String),
+}
+
+// Every command other than setup needs a finished setup to work with
+pub(crate) fn require_setup(settings: &mut RwLockWriteGuard<'_, Settings>, setup_complete: bool) -> Result<()> {
+    if setup::read_progress(settings).is_some() {
+        return Err(NcmError::Setup(ERR_SETUP_INTERRUPTED.to_string()));
+    }
+
+    if !setup_complete && !settings.nvim_path.is_symlink() {
+        return Err(NcmError::Setup(ERR_RUN_SETUP.to_string()));
+    }
+
+    settings.check_directories().map_err(|e| NcmError::Setup(format!("{}: {e}", ERR_DIR_UCREATE)))
+}
+
+// Run the first-time setup, or resume or roll back one that was interrupted
+pub(crate) fn run_setup(options: &SetupOptions, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    if let Some(progress) = setup::read_progress(settings) {
+        return resume_setup(progress, options, settings);
     }
 
-    if settings.nvim_path.is_symlink() {
-        nvim_symlinked = true;
+    if options.rollback {
+        return Err(NcmError::NotFound(ERR_NO_ROLLBACK.to_string()));
     }
 
-    if !nvim_symlinked && !setup_complete {
-        info!("{}", INFO_NEW_SETUP);
+    let setup_complete = settings.settings.getbool(NCM, SETUP_COMPLETE)
+        .map_err(NcmError::Settings)?
+        .unwrap_or(false);
+
+    if setup_complete || settings.nvim_path.is_symlink() {
+        info!("{}", INFO_SETUP_ALREADY);
+        return settings.check_directories().map_err(|e| NcmError::Setup(format!("{}: {e}", ERR_DIR_UCREATE)));
+    }
 
-        // --| Backup original and move to new location
-        return if let Ok(backup_info) = backup_original(settings) {
-            let mut nvim_tmp = PathBuf::new();
-            let mut nvim_data = PathBuf::new();
-            let name = backup_info.name.as_str();
-            nvim_data.push(&settings.ncm_paths.local);
-            nvim_data.push(name);
+    // --| Nothing to back up, start from an empty registry instead
+    if !settings.nvim_path.exists() || is_empty_dir(&settings.nvim_path) {
+        return bootstrap_setup(options, settings);
+    }
 
-            let description: Option<String> = Some(DEFAULT_CONFIG_DESC.to_owned());
-            let data_path: Option<String> = Some(nvim_data.to_str().unwrap().to_string());
-            let cache_path: Option<String> = Some(settings.ncm_paths.cache.to_str().unwrap().to_string());
+    check_nvim_found(settings)?;
+    info!("{}", INFO_NEW_SETUP);
 
-            nvim_tmp.push(backup_info.path.as_str());
-            nvim_tmp.push(backup_info.name.as_str());
+    let progress = prepare_setup(settings, options)?;
+    setup::write_progress(settings, &progress)?;
+    continue_setup(progress, options, settings)
+}
 
-            if configs::add_config(
-                settings.configs_path.to_str().unwrap(),
-                ConfigData {
-                    name: name.parse()?,
-                    path: nvim_tmp.to_str().unwrap().to_string(),
-                    description: description.clone(),
-                    data_path,
-                    cache_path,
-                },
-            ).is_ok() {
-                load_config(&Some(name.to_string()), settings);
-                info!("{}: {name:?} {nvim_tmp:?}", INFO_CONFIGS_ADDED);
+// First run without an existing nvim config. There is nothing to back up,
+// so create the ncm directories and registry and offer to add a first configuration
+fn bootstrap_setup(options: &SetupOptions, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    info!("{}", INFO_BOOTSTRAP);
+    settings.check_directories().map_err(|e| NcmError::Setup(format!("{}: {e}", ERR_DIR_UCREATE)))?;
+
+    let first = match (&options.from, &options.template) {
+        (Some(dir), _) => Some(FirstConfig::Directory(dir.clone())),
+        (None, Some(template)) => Some(FirstConfig::Template(template.to_string())),
+        (None, None) if !options.interactive => None,
+        (None, None) => prompt_first_config(settings)?,
+    };
 
-                settings.settings
-                    .set(NCM, SETUP_COMPLETE, Some("true".to_string()))
-                    .expect(ERR_SETTINGS_WRITE);
+    let Some(first) = first else {
+        complete_bootstrap(settings)?;
+        let setup_complete = RGB(146, 181, 95).paint(INFO_SETUP_COMPLETE);
+        info!("{} {}", setup_complete, INFO_BOOTSTRAP_ADD_LATER);
+        return Ok(());
+    };
 
-                settings.write_settings().expect(ERR_SETTINGS_WRITE);
+    let name = match &options.name {
+        Some(name) => name.to_string(),
+        None if !options.interactive => MAIN.to_string(),
+        None => Text::new(INFO_CONFIG_NAME).with_default(MAIN).prompt()?,
+    };
 
-                let setup_complete = RGB(146, 181, 95).paint(INFO_SETUP_COMPLETE);
-                info!("{}\n", setup_complete);
-            } else {
-                error!("{}: {} {name:?} {nvim_tmp:?} {description:?}", ERR_CONFIGS_ADD, settings.configs_path.to_str().unwrap());
-                return Err(anyhow!("{}: {} {name:?} {nvim_tmp:?} {description:?}", ERR_CONFIGS_ADD, settings.configs_path.to_str().unwrap()));
+    // --| Setup is only marked complete once the first config is added and loaded
+    let (path, description) = match first {
+        FirstConfig::Directory(dir) => {
+            if !check_for_nvim(&dir) {
+                return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, dir));
             }
-            Ok(())
-        } else {
-            Err(anyhow!("{}", ERR_NOT_COMPLETE))
-        };
-    } else {
-        settings.check_directories().expect("Error creating directories");
+            (dir, None)
+        }
+        FirstConfig::Template(template_name) => {
+            let (destination, description) = scaffold(&name, &template_name, settings)?;
+            (destination, Some(description))
+        }
+    };
+
+    let config_json = path_str(&settings.configs_path)?.to_string();
+    let data_path = Some(path_str(&settings.ncm_paths.local)?.to_string());
+    let cache_path = Some(path_str(&settings.ncm_paths.cache)?.to_string());
+
+    let added = add_config(&name, &path, &description, data_path, cache_path, &config_json)
+        .and_then(|_| load_config(&Some(name.to_string()), &None, settings));
+
+    // --| Undo a half created first config so setup can simply be run again
+    if let Err(e) = added {
+        if configs::get_config(&config_json, &name).is_ok() {
+            configs::remove_config(&name, &config_json)?;
+        }
+        if description.is_some() && path.starts_with(&settings.ncm_paths.config) {
+            std::fs::remove_dir_all(&path)?;
+        }
+        return Err(e);
     }
+    complete_bootstrap(settings)?;
 
+    let setup_complete = RGB(146, 181, 95).paint(INFO_SETUP_COMPLETE);
+    info!("{}\n", setup_complete);
     Ok(())
 }
 
-// --| Backup ------------------------------
-// --|--------------------------------------
-pub(crate) fn initiate_backup(name: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) {
-    let config_path = PathBuf::from_str(settings.configs_path.to_str().unwrap()).ok().unwrap();
-    let config_file = std::fs::read_to_string(config_path).expect(ERR_CONFIGS_READ);
-    let configs: Configs = serde_json::from_str(&config_file).expect(ERR_CONFIGS_PARSE);
-    let config_name: String;
-
-    if let Some(n) = name {
-        config_name = n.to_string();
-    } else {
-        let mut options = Vec::new();
+fn complete_bootstrap(settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    settings.settings.set(NCM, SETUP_COMPLETE, Some("true".to_string()));
+    settings.write_settings().map_err(|e| NcmError::Settings(e.to_string()))
+}
 
-        for cfg in &configs.configs {
-            options.push(cfg.name.to_string());
-        }
+fn prompt_first_config(settings: &Settings) -> Result<Option<FirstConfig>> {
+    prompt::require_terminal(INFO_BOOTSTRAP_SELECT)?;
+    let choice = Select::new(INFO_BOOTSTRAP_SELECT, vec![INFO_BOOTSTRAP_LOCAL, INFO_BOOTSTRAP_TEMPLATE, INFO_BOOTSTRAP_SKIP]).prompt()?;
+
+    Ok(match choice {
+        INFO_BOOTSTRAP_LOCAL => Some(FirstConfig::Directory(PathBuf::from(Text::new(INFO_BOOTSTRAP_PATH).prompt()?))),
+        INFO_BOOTSTRAP_TEMPLATE => Some(FirstConfig::Template(select_template(settings)?)),
+        _ => None,
+    })
+}
 
-        options.push(INFO_SELECT_ALL.to_string());
-        config_name = Select::new(INFO_BACKUP_SELECT, options).prompt().unwrap();
+fn is_empty_dir(path: &Path) -> bool {
+    path.is_dir() && path.read_dir().is_ok_and(|mut entries| entries.next().is_none())
+}
+
+fn check_nvim_found(settings: &Settings) -> Result<()> {
+    if check_for_nvim(&settings.nvim_path) {
+        return Ok(());
     }
 
-    if config_name == INFO_SELECT_ALL {
-        for cfg in &configs.configs {
-            backup_selected(&settings, &configs, &cfg.name);
+    if !&settings.xdg_config_is_set {
+        if cfg!(windows) {
+            warn!("{} {} ", ERR_NVIM_NOT_FOUND_WIN, ERR_NVIM_NOT_FOUND_WIN_NO_XDG);
+        } else {
+            warn!("{} {} ", ERR_NVIM_NOT_FOUND_LINUX, ERR_NVIM_NOT_FOUND_LINUX_NO_XDG);
         }
+    } else if cfg!(windows) {
+        warn!("{} {} ", ERR_NVIM_NOT_FOUND, ERR_NVIM_NOT_FOUND_WIN_XDG);
     } else {
-        backup_selected(&settings, &configs, &config_name);
+        warn!("{}", ERR_NVIM_NOT_FOUND_LINUX);
     }
+    Err(NcmError::Setup(format!("{}: {:?}", ERR_NVIM_NOT_FOUND, settings.nvim_path)))
 }
 
-fn backup_selected(settings: &&mut RwLockWriteGuard<Settings>, configs: &Configs, config_name: &String) {
-    let mut backup_info = BackupInfo::new();
-    for cfg in &configs.configs {
-        if cfg.name == config_name.as_str() {
-            backup_info.name = cfg.name.to_string();
-            backup_info.path = cfg.path.to_string();
+fn resume_setup(progress: SetupProgress, options: &SetupOptions, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let interrupted = format!("{} '{}'", INFO_SETUP_INTERRUPTED, progress.stage.label());
+    warn!("{}", interrupted);
+
+    let rollback = if options.rollback {
+        true
+    } else if options.resume || !options.interactive {
+        false
+    } else {
+        prompt::require_terminal(&interrupted)?;
+        Select::new(&interrupted, vec![INFO_SETUP_RESUME, INFO_SETUP_ROLLBACK]).prompt()? == INFO_SETUP_ROLLBACK
+    };
+
+    if rollback {
+        for action in setup::rollback(settings, &progress)? {
+            info!("{} {}", green_text(CHECK), action);
         }
+
+        let rolled_back = RGB(146, 181, 95).paint(INFO_SETUP_ROLLED_BACK);
+        info!("{}", rolled_back);
+        return Ok(());
     }
 
-    let mut backup_path = PathBuf::new();
-    let mut backup_source = PathBuf::new();
+    info!("{} {:?}", INFO_SETUP_RESUMING, progress.name);
+    continue_setup(progress, options, settings)
+}
 
-    backup_path.push(&settings.ncm_cfg_path);
-    backup_path.push(BACKUPS);
+// Ask where the original configuration should go, and under which name
+fn prepare_setup(settings: &Settings, options: &SetupOptions) -> Result<SetupProgress> {
+    let result = prompt::confirm(Confirm::new(INFO_SETUP_CONFIRM)
+        .with_default(true)
+        .with_help_message(HELP_BACKUP_MSG), !options.interactive)?;
 
-    if !backup_path.exists() {
-        std::fs::create_dir_all(&backup_path).expect(ERR_BACKUP_PATH);
+    if !result {
+        warn!("{}", ERR_BACKUP_MANUALLY);
+        return Err(NcmError::Aborted);
     }
 
-    backup_path.push(format!("{}.{}", &config_name, ZIP));
+    let default_path = path_str(&settings.ncm_paths.config)?.to_string();
+
+    let nvim_config_path = match &options.config_root {
+        Some(root) => path_str(root)?.to_string(),
+        None if !options.interactive => default_path,
+        None => Text {
+            message: INFO_CONFIG_PATH,
+            initial_value: None,
+            default: Some(default_path.as_str()),
+            placeholder: Some(INFO_CONFIG_PATH_PLACEHOLDER),
+            help_message: Some(HELP_CONFIG_PATH),
+            formatter: Text::DEFAULT_FORMATTER,
+            validators: Vec::new(),
+            page_size: Text::DEFAULT_PAGE_SIZE,
+            autocompleter: None,
+            render_config: RenderConfig::default(),
+        }.prompt()?,
+    };
+
+    let nvim_config_name = match &options.name {
+        Some(name) => name.to_string(),
+        None if !options.interactive => MAIN.to_string(),
+        None => Text {
+            message: INFO_CONFIG_NAME,
+            initial_value: None,
+            default: Some(MAIN),
+            placeholder: Some(INFO_CONFIG_NAME_PLACEHOLDER),
+            help_message: Some(HELP_CONFIG_NAME),
+            formatter: Text::DEFAULT_FORMATTER,
+            validators: Vec::new(),
+            page_size: Text::DEFAULT_PAGE_SIZE,
+            autocompleter: None,
+            render_config: RenderConfig::default(),
+        }.prompt()?,
+    };
+
+    Ok(SetupProgress {
+        stage: SetupStage::Started,
+        name: nvim_config_name,
+        config_root: PathBuf::from(nvim_config_path),
+    })
+}
+
+// Run the remaining setup steps, recording progress after each one
+fn continue_setup(mut progress: SetupProgress, options: &SetupOptions, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    if progress.stage < SetupStage::BackedUp {
+        if options.backup {
+            backup_original(settings, &progress.name)?;
+        } else {
+            info!("{}", INFO_SETUP_NO_BACKUP);
+        }
+        progress.stage = SetupStage::BackedUp;
+        setup::write_progress(settings, &progress)?;
+    }
+
+    if progress.stage < SetupStage::ConfigMoved {
+        move_original_config(settings, &progress)?;
+        progress.stage = SetupStage::ConfigMoved;
+        setup::write_progress(settings, &progress)?;
+    }
+
+    if progress.stage < SetupStage::DataCopied {
+        copy_original_data(settings, &progress)?;
+        progress.stage = SetupStage::DataCopied;
+        setup::write_progress(settings, &progress)?;
+    }
+
+    if progress.stage < SetupStage::Registered {
+        register_original(settings, &progress)?;
+        progress.stage = SetupStage::Registered;
+        setup::write_progress(settings, &progress)?;
+    }
+
+    load_config(&Some(progress.name.to_string()), &None, settings)?;
+
+    settings.settings.set(NCM, SETUP_COMPLETE, Some("true".to_string()));
+    setup::clear_progress(settings)?;
+
+    let setup_complete = RGB(146, 181, 95).paint(INFO_SETUP_COMPLETE);
+    info!("{}\n", setup_complete);
+    Ok(())
+}
+
+// --| Setup Steps -------------------------
+fn register_original(settings: &Settings, progress: &SetupProgress) -> Result<()> {
+    let config_json = path_str(&settings.configs_path)?;
+    if configs::get_config(config_json, &progress.name).is_ok() {
+        info!("{}: {:?}", INFO_SETUP_REGISTERED, progress.name);
+        return Ok(());
+    }
+
+    let config_path = progress.config_path();
+    configs::add_config(
+        config_json,
+        ConfigData {
+            name: progress.name.to_string(),
+            path: path_str(&config_path)?.to_string(),
+            description: Some(DEFAULT_CONFIG_DESC.to_owned()),
+            data_path: Some(path_str(&progress.data_path(settings))?.to_string()),
+            cache_path: Some(path_str(&settings.ncm_paths.cache)?.to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    info!("{}: {:?} {:?}", INFO_CONFIGS_ADDED, progress.name, config_path);
+    Ok(())
+}
+
+fn backup_original(settings: &mut RwLockWriteGuard<Settings>, name: &str) -> Result<()> {
+    let backup_path = settings.ncm_cfg_path.join(BACKUPS);
+
+    if !backup_path.exists() {
+        std::fs::create_dir_all(&backup_path)?;
+    }
 
-    let backup_str = backup_path.to_str().unwrap();
+    let backup_str = path_str(&backup_path)?.to_string();
     debug!("{}: {}", INFO_BACKUP_PATH, backup_str);
 
+    settings.settings.setstr(NCM, BACKUP_PATH, Some(&backup_str));
+
+    let backup_file = backup_path.join(format!("{}.{}", name, ZIP));
+
     let creating_backup_path = RGB(146, 181, 95).paint(INFO_BACKUP_PATH_AT);
-    info!("{} {}", creating_backup_path, backup_path.to_str().unwrap());
+    info!("{} {}\n", creating_backup_path, backup_file.display());
 
-    backup_source.push(backup_info.path.as_str());
+    create_backup(settings.nvim_path.as_path(), &backup_file)
+        .map_err(|e| NcmError::Backup(format!("{}: {:?}", ERR_BACKUP_CREATE, e)))?;
 
-    // --| Perform Backup -------------------
-    match create_backup(backup_source.as_path(), backup_path.as_path()) {
+    if !backup_file.exists() {
+        return Err(NcmError::Backup(ERR_BACKUP_CREATE.to_string()));
+    }
+
+    let backup_success = RGB(146, 181, 95).paint(INFO_BACKUP_COMPLETE);
+    info!("{}\n", backup_success);
+    Ok(())
+}
+
+fn move_original_config(settings: &Settings, progress: &SetupProgress) -> Result<()> {
+    let config_path = progress.config_path();
+
+    // --| Interrupted after the move, but before it was recorded
+    if config_path.exists() && !settings.nvim_path.exists() {
+        return Ok(());
+    }
+
+    // --| Create NCM Config Directory ------------------
+    std::fs::create_dir_all(&progress.config_root)
+        .map_err(|e| NcmError::Setup(format!("{}: {e}", ERR_CREATE_CONFIG_DIR)))?;
+
+    // --| Create NCM Data Directory --------------------
+    std::fs::create_dir_all(&settings.ncm_paths.local)
+        .map_err(|e| NcmError::Setup(format!("{}: {e}", ERR_CREATE_DATA_DIR)))?;
+
+    // --| Copy Original Config to New Config Path -------
+    let mut sp = Spinner::with_stream(Spinners::Dots12, format!("{}: {:?}", INFO_MOVING_ORIGINAL, progress.config_root), Stream::Stderr);
+
+    let moved = if cfg!(target_os = "windows") {
+        fs_extra::copy_items(&[&settings.nvim_path], &progress.config_root, &CopyOptions::new().copy_inside(true))
+            .map_err(|e| e.to_string())
+            .and_then(|_| std::fs::rename(progress.config_root.join(NVIM), &config_path).map_err(|e| e.to_string()))
+    } else {
+        cleanup::move_path(&settings.nvim_path, &config_path).map_err(|e| e.to_string())
+    };
+
+    match moved {
         Ok(_) => {
-            if backup_path.exists() {
-                let backup_success = RGB(146, 181, 95).paint(INFO_BACKUP_COMPLETE);
-                info!("{}", backup_success);
-            } else {
-                error!("{}", ERR_BACKUP_CREATE);
-            }
+            sp.stop_and_persist(&green_text("✔"), INFO_MOVING_ORIGINAL_COMPLETE.into());
+            Ok(())
         }
         Err(e) => {
-            error!("{}: {:?}",ERR_BACKUP_CREATE, e);
+            sp.stop_and_persist(&red_text("✘"), FAILED.into());
+            Err(NcmError::Setup(format!("{}: {e}", ERR_COPY_CONFIG_DIR)))
         }
     }
 }
 
-fn backup_original(settings: &mut RwLockWriteGuard<Settings>) -> Result<BackupInfo> {
-    let result = Confirm::new("Create Configuration Directory?")
-        .with_default(true)
-        .with_help_message(HELP_BACKUP_MSG)
-        .prompt()
-        .unwrap();
+fn copy_original_data(settings: &Settings, progress: &SetupProgress) -> Result<()> {
+    let new_data_path = progress.data_path(settings);
 
-    if !result {
-        warn!("{}", ERR_BACKUP_MANUALLY);
-        return Err(anyhow!(ERR_BACKUP_MANUALLY));
+    // --| Copy Data to New Data Path --------------------
+    if !&new_data_path.exists() { std::fs::create_dir_all(&new_data_path)?; }
+    let mut sp = Spinner::with_stream(Spinners::Dots12, format!("{}: {:?}", INFO_MOVING_DATA, new_data_path), Stream::Stderr);
+
+    // --| Had to make a custom copy function because move_dir() and 
+    // --| fs_extra::copy_items() were erroring on tree-sitter symlinks
+    match copy_recursively(&settings.nvim_paths.local, &new_data_path) {
+        Ok(_) => {
+            sp.stop_and_persist(&green_text("✔"), INFO_MOVING_DATA_COMPLETE.into());
+            Ok(())
+        }
+        Err(e) => {
+            sp.stop_and_persist(&red_text("✘"), FAILED.into());
+            Err(NcmError::Setup(format!("{}: {e}", ERR_COPY_DATA_DIR)))
+        }
     }
+}
 
-    let mut backup_info = BackupInfo {
-        name: NCM_DATA.to_string(),
-        path: settings.ncm_paths.config.to_str().unwrap().to_string(),
-    };
+// --| Backup ------------------------------
+// --|--------------------------------------
+// List the archives in the backups directory
+pub(crate) fn list_backups(format: OutputFormat, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let list = backup::list_backups(&settings.ncm_cfg_path.join(BACKUPS))?;
 
-    let nvim_config_path = Text {
-        message: INFO_CONFIG_PATH,
-        initial_value: None,
-        default: Some(backup_info.path.as_str()),
-        placeholder: Some(INFO_CONFIG_PATH_PLACEHOLDER),
-        help_message: Some(HELP_CONFIG_PATH),
-        formatter: Text::DEFAULT_FORMATTER,
-        validators: Vec::new(),
-        page_size: Text::DEFAULT_PAGE_SIZE,
-        autocompleter: None,
-        render_config: RenderConfig::default(),
-    }.prompt();
+    if format.is_structured() {
+        return Ok(output::emit(format, OUTPUT_KIND_BACKUPS, &list)?);
+    }
 
-    let nvim_config_name = Text {
-        message: INFO_CONFIG_NAME,
-        initial_value: None,
-        default: Some(MAIN),
-        placeholder: Some(INFO_CONFIG_NAME_PLACEHOLDER),
-        help_message: Some(HELP_CONFIG_NAME),
-        formatter: Text::DEFAULT_FORMATTER,
-        validators: Vec::new(),
-        page_size: Text::DEFAULT_PAGE_SIZE,
-        autocompleter: None,
-        render_config: RenderConfig::default(),
-    }.prompt().unwrap();
+    let backups_str = RGB(70, 130, 180).paint(CLI_BACKUPS);
+    println!("{}", backups_str);
+    println!("{}", CLI_SPACER);
 
-    if let Ok(new_config_path) = &nvim_config_path {
-        let mut backup_path = PathBuf::new();
-        let mut backup_file = PathBuf::new();
+    let name_str = RGB(70, 130, 180).paint(CLI_TABLE_NAME);
+    let size_str = RGB(70, 130, 180).paint(CLI_TABLE_SIZE);
+    let modified_str = RGB(70, 130, 180).paint(CLI_TABLE_CREATED);
+    let path_str = RGB(70, 130, 180).paint(CLI_TABLE_PATH);
 
-        backup_path.push(&settings.ncm_cfg_path);
-        backup_path.push(BACKUPS);
+    let mut table = Table::new();
+    table.set_titles(row![b->name_str, b->size_str, b->modified_str, b->path_str]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
 
-        if !backup_path.exists() {
-            std::fs::create_dir_all(&backup_path).expect(ERR_BACKUP_PATH);
-        }
+    for b in &list.backups {
+        table.add_row(row![b.name, r->cleanup::format_size(b.size), format_timestamp(&b.modified), b.path.display()]);
+    }
 
-        let backup_str = backup_path.to_str().unwrap();
-        debug!("{}: {}", INFO_BACKUP_PATH, backup_str);
+    table.printstd();
+    Ok(())
+}
 
-        settings.settings
-            .setstr(NCM, BACKUP_PATH, Option::from(backup_str))
-            .expect(ERR_BACKUP_PATH);
+pub(crate) fn initiate_backup(name: &Option<String>, tag: &Option<String>, settings: &mut RwLockWriteGuard<Settings>) -> Result<()> {
+    let configs = configs::list_configs(path_str(&settings.configs_path)?)?;
 
-        backup_file.push(backup_path.to_str().unwrap());
-        backup_file.push(format!("{}.{}", &nvim_config_name, ZIP));
+    let config_name = match name {
+        Some(n) => n.to_string(),
+        None => select_config(INFO_BACKUP_SELECT, &configs, tag, true)?,
+    };
 
-        let creating_backup_path = RGB(146, 181, 95).paint(INFO_BACKUP_PATH_AT);
-        info!("{} {}\n", creating_backup_path, backup_file.to_str().unwrap());
+    if config_name == INFO_SELECT_ALL {
+        for cfg in &configs.configs {
+            if let Some(t) = tag {
+                if !cfg.has_tag(t) { continue; }
+            }
+            backup_selected(settings, &configs, &cfg.name)?;
+        }
+    } else {
+        backup_selected(settings, &configs, &config_name)?;
+    }
 
-        // --| Perform Backup -------------------
-        perform_backup(settings, new_config_path, &backup_file, &nvim_config_name)?;
+    Ok(())
+}
 
-        let mut init_lua = PathBuf::new();
-        init_lua.push(new_config_path);
-        init_lua.push(&nvim_config_name);
-        init_lua.push(INIT_LUA);
+fn backup_selected(settings: &Settings, configs: &Configs, config_name: &str) -> Result<()> {
+    let cfg = configs.configs.iter()
+        .find(|cfg| cfg.name == config_name)
+        .ok_or_else(|| NcmError::ConfigNotFound(config_name.to_string()))?;
 
-        let mut init_vim = PathBuf::new();
-        init_vim.push(new_config_path);
-        init_vim.push(&nvim_config_name);
-        init_vim.push(INIT_VIM);
+    let mut backup_path = settings.ncm_cfg_path.join(BACKUPS);
 
-        if !init_lua.exists() && !init_vim.exists() {
-            return Err(anyhow!(ERR_DIR_CONFIG_VERIFICATION));
-        }
+    if !backup_path.exists() {
+        std::fs::create_dir_all(&backup_path)?;
     }
 
-    backup_info.name = nvim_config_name;
-    backup_info.path = nvim_config_path.unwrap();
-    settings.settings.write(&settings.settings_path).expect(ERR_SETTINGS_WRITE);
-
-    Ok(backup_info)
-}
+    backup_path.push(format!("{}.{}", &config_name, ZIP));
+    debug!("{}: {}", INFO_BACKUP_PATH, backup_path.display());
 
-// --| Perform Backup -----------------------------
-pub(crate) fn perform_backup(settings: &mut RwLockWriteGuard<Settings>, new_config_path: &String, backup_path: &Path, name: &str) -> Result<()> {
-    match create_backup(settings.nvim_path.as_path(), backup_path) {
-        Ok(_) => {
-            if backup_path.exists() {
-                let backup_success = RGB(146, 181, 95).paint(INFO_BACKUP_COMPLETE);
-
-                info!("{}\n", backup_success);
-
-                // --| Create NCM Config Directory ------------------
-                if !Path::new(new_config_path).exists() {
-                    match std::fs::create_dir_all(new_config_path) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            error!("{}: {:?}", ERR_CREATE_CONFIG_DIR, e);
-                            return Err(anyhow!("{}: {e}",ERR_CREATE_CONFIG_DIR));
-                        }
-                    }
-                }
+    let creating_backup_path = RGB(146, 181, 95).paint(INFO_BACKUP_PATH_AT);
+    info!("{} {}", creating_backup_path, backup_path.display());
 
-                // --| Create NCM Data Directory --------------------
-                if !&settings.ncm_paths.local.exists() {
-                    match std::fs::create_dir_all(&settings.ncm_paths.local) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            error!("{}: {:?}", ERR_CREATE_DATA_DIR, e);
-                            return Err(anyhow!("{}: {e}",ERR_CREATE_DATA_DIR));
-                        }
-                    }
-                }
+    // --| Single-file configs share their directory with unrelated files
+    let backup_source = cfg.entry_file().unwrap_or_else(|| PathBuf::from(&cfg.path));
 
-                let mut rename_path = PathBuf::from(new_config_path);
-                let mut rename_original = PathBuf::from(new_config_path);
-                let mut new_data_path = PathBuf::from(&settings.ncm_paths.local);
-                let mut rename_data_path = PathBuf::from(&settings.ncm_paths.local);
-
-                rename_path.push(name);
-                new_data_path.push(name);
-                rename_original.push(NVIM);
-                rename_data_path.push(settings.nvim_paths.local.file_name().unwrap());
-
-                let mut sp: Spinner;
-
-                if cfg!(not(target_os = "windows"))
-                {
-                    // --| Copy Original Config to New Config Path -------
-                    sp = Spinner::new(Spinners::Dots12, format!("{}: {:?}", INFO_MOVING_ORIGINAL, new_config_path));
-
-                    match move_dir(&settings.nvim_path, new_config_path, &CopyOptions::new().copy_inside(true)) {
-                        Ok(_) => {
-                            sp.stop_and_persist("✔", INFO_MOVING_ORIGINAL_COMPLETE.into());
-                            std::fs::rename(&rename_original, &rename_path).expect(ERR_DIR_DATA_RENAME);
-                        }
-                        Err(e) => {
-                            sp.stop_and_persist("✘", FAILED.into());
-                            return Err(anyhow!("{}: {e}", ERR_COPY_CONFIG_DIR));
-                        }
-                    }
-
-                    // --| Copy Data to New Data Path --------------------
-                    if !&new_data_path.exists() { std::fs::create_dir_all(&new_data_path).expect("Failed to create new data path"); }
-                    sp = Spinner::new(Spinners::Dots12, format!("{}: {:?}", INFO_MOVING_DATA, new_data_path));
-
-                    // --| Had to make a custom copy function because move_dir() and 
-                    // --| fs_extra::copy_items() were erroring on tree-sitter symlinks
-                    match copy_recursively(&settings.nvim_paths.local, &new_data_path) {
-                        Ok(_) => {
-                            sp.stop_and_persist(&green_text("✔"), INFO_MOVING_DATA_COMPLETE.into());
-                        }
-                        Err(e) => {
-                            sp.stop_and_persist(&red_text("✘"), FAILED.into());
-                            return Err(anyhow!("{}: {e}", ERR_COPY_DATA_DIR));
-                        }
-                    }
-                }
+    // --| Perform Backup -------------------
+    create_backup(backup_source.as_path(), backup_path.as_path())
+        .map_err(|e| NcmError::Backup(format!("{}: {:?}", ERR_BACKUP_CREATE, e)))?;
 
-                if cfg!(target_os = "windows")
-                {
-                    // --| Copy Original Config to New Config Path -------
-                    sp = Spinner::new(Spinners::Dots12, format!("{}: {:?}", INFO_MOVING_ORIGINAL, new_config_path));
-
-                    match fs_extra::copy_items(&[&settings.nvim_path], new_config_path, &CopyOptions::new().copy_inside(true)) {
-                        Ok(_) => {
-                            sp.stop_and_persist(&green_text("✔"), INFO_MOVING_ORIGINAL_COMPLETE.into());
-                            std::fs::rename(rename_original, rename_path).expect(ERR_DIR_DATA_RENAME);
-                        }
-                        Err(e) => {
-                            sp.stop_and_persist(&red_text("✘"), FAILED.into());
-                            return Err(anyhow!("{}: {e}", ERR_COPY_CONFIG_DIR));
-                        }
-                    }
-
-                    // --| Copy Data to New Data Path --------------------
-                    if !&new_data_path.exists() { std::fs::create_dir_all(&new_data_path).expect("Failed to create new data path"); }
-                    sp = Spinner::new(Spinners::Dots12, format!("{}: {:?}", INFO_MOVING_DATA, new_data_path));
-
-                    match copy_recursively(&settings.nvim_paths.local, &new_data_path) {
-                        Ok(_) => {
-                            sp.stop_and_persist(&green_text("✔"), INFO_MOVING_DATA_COMPLETE.into());
-                        }
-                        Err(e) => {
-                            sp.stop_and_persist(&red_text("✘"), FAILED.into());
-                            return Err(anyhow!("{}: {e}", ERR_COPY_DATA_DIR));
-                        }
-                    }
-                }
-            } else {
-                error!("{}", ERR_BACKUP_CREATE);
-                return Err(anyhow!(ERR_BACKUP_CREATE));
-            }
-        }
-        Err(e) => {
-            error!("{}: {:?}",ERR_BACKUP_CREATE, e);
-            return Err(anyhow!("{}: {:?}", ERR_BACKUP_CREATE, e));
-        }
+    if !backup_path.exists() {
+        return Err(NcmError::Backup(ERR_BACKUP_CREATE.to_string()));
     }
 
+    let backup_success = RGB(146, 181, 95).paint(INFO_BACKUP_COMPLETE);
+    info!("{}", backup_success);
     Ok(())
 }
 
@@ -611,10 +1953,8 @@ pub fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>)
                     debug!("{}: {:?}", INFO_MOVING_DATA, &destination.as_ref().join(entry.file_name()));
                 }
                 Err(e) => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("{}: {} {:?}", ERR_COPY_DATA_DIR, &entry.path().to_str().unwrap(), e))
-                    );
+                    return Err(std::io::Error::other(
+                        format!("{}: {} {:?}", ERR_COPY_DATA_DIR, entry.path().display(), e)));
                 }
             }
         } else {
@@ -623,10 +1963,8 @@ pub fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>)
                     debug!("{}: {:?}", INFO_MOVING_DATA, &destination.as_ref().join(entry.file_name()));
                 }
                 Err(e) => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("{}: {} {:?}", ERR_COPY_DATA_FILE, &entry.path().to_str().unwrap(), e))
-                    );
+                    return Err(std::io::Error::other(
+                        format!("{}: {} {:?}", ERR_COPY_DATA_FILE, entry.path().display(), e)));
                 }
             }
         }
@@ -634,11 +1972,96 @@ pub fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>)
     Ok(())
 }
 
+// --| Tag ---------------------------------
+// --|--------------------------------------
+pub(crate) fn tag_config(action: &TagAction, config_json: &str) -> Result<()> {
+    let cfg = match action {
+        TagAction::Add { name, tags } => configs::add_tags(config_json, name, tags)?,
+        TagAction::Remove { name, tags } => configs::remove_tags(config_json, name, tags)?,
+    };
+
+    info!("{}: {:?} {:?}", INFO_CONFIGS_TAGS, cfg.name, cfg.tags);
+    Ok(())
+}
+
+// --| Select ------------------------------
+// Prompt for a configuration, optionally restricted to those carrying `tag`.
+// Each entry shows its tags, description and path, all of which can be
+// fuzzy matched against, and the current default is marked.
+fn select_config(message: &str, configs: &Configs, tag: &Option<String>, include_all: bool) -> Result<String> {
+    let candidates: Vec<&ConfigData> = configs.configs.iter()
+        .filter(|cfg| tag.as_ref().is_none_or(|t| cfg.has_tag(t)))
+        .collect();
+
+    if candidates.is_empty() {
+        return match tag {
+            Some(t) => Err(NcmError::NotFound(format!("{} {t}", ERR_CONFIGS_TAG))),
+            None => Err(NcmError::NotFound(ERR_CONFIGS_EMPTY.to_string())),
+        };
+    }
+
+    let names: Vec<String> = candidates.iter()
+        .map(|cfg| if cfg.tags.is_empty() { cfg.name.to_string() } else { format!("{} [{}]", cfg.name, cfg.tags.join(", ")) })
+        .collect();
+
+    let name_width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+    let desc_width = candidates.iter().map(|cfg| cfg.description.as_deref().unwrap_or("").chars().count()).max().unwrap_or(0);
+
+    let mut options: Vec<String> = candidates.iter().zip(names.iter())
+        .map(|(cfg, name)| {
+            let marker = if cfg.name == configs.configs_default { CHECK } else { CLI_SPACER };
+            let desc = cfg.description.as_deref().unwrap_or("");
+            format!("{marker} {name:<name_width$}  {desc:<desc_width$}  {}", cfg.path)
+        })
+        .collect();
+
+    if include_all { options.push(format!("{CLI_SPACER} {INFO_SELECT_ALL}")); }
+
+    let starting_cursor = candidates.iter().position(|cfg| cfg.name == configs.configs_default).unwrap_or(0);
+
+    prompt::require_terminal(message)?;
+    let selected = Select::new(message, options)
+        .with_filter(&|input, _, value, _| fuzzy_match(input, value))
+        .with_starting_cursor(starting_cursor)
+        .with_help_message(HELP_SELECT_CONFIG)
+        .raw_prompt()?;
+
+    match candidates.get(selected.index) {
+        Some(cfg) => Ok(cfg.name.to_string()),
+        None => Ok(INFO_SELECT_ALL.to_string()),
+    }
+}
+
+// Case-insensitive subsequence match, so "lzv" matches "lazyvim"
+fn fuzzy_match(pattern: &str, value: &str) -> bool {
+    let mut chars = value.chars().flat_map(char::to_lowercase);
+    pattern.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|p| chars.any(|c| c == p))
+}
+
 // --| Helper Functions -----------------------------------
-fn green_text(text: &str) -> ANSIGenericString<str> {
+// Paths are stored in the registry as strings
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| NcmError::invalid_path(ERR_PATH_UTF8, path))
+}
+
+fn format_timestamp(timestamp: &Option<DateTime<Utc>>) -> String {
+    match timestamp {
+        Some(t) => t.with_timezone(&Local).format(TIMESTAMP_FORMAT).to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn green_text(text: &str) -> ANSIGenericString<'_, str> {
     RGB(146, 181, 95).paint(text)
 }
 
-fn red_text(text: &str) -> ANSIGenericString<str> {
+fn red_text(text: &str) -> ANSIGenericString<'_, str> {
     RGB(253, 53, 49).paint(text)
 }
+
+fn yellow_text(text: &str) -> ANSIGenericString<'_, str> {
+    RGB(229, 192, 123).paint(text)
+}
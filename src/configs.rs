@@ -1,10 +1,9 @@
-#![allow(unused_assignments)]
-
-use anyhow::anyhow;
-use std::path::PathBuf;
-use serde_json::Result;
-use serde::{de::Error, Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use crate::constants::*;
+use crate::errors::{NcmError, Result};
+use crate::output::TsvRows;
 
 // Configuration Data Container
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -15,177 +14,270 @@ pub struct Configs {
 }
 
 // Configuration Data Structure
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ConfigData {
     pub name: String,
     pub path: String,
     pub description: Option<String>,
     pub data_path: Option<String>,
     pub cache_path: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_loaded_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub load_count: u64,
+    /// Remote the config was cloned from with 'ncm add --git'
+    pub remote: Option<String>,
+    pub git_ref: Option<String>,
+    /// Commit the config is expected to be at, set with 'ncm pin'
+    pub pinned: Option<String>,
+    /// Config this one is a git worktree of, created with 'ncm add --worktree'
+    pub worktree_of: Option<String>,
+    /// Trial added with 'ncm try', removed by 'ncm gc' once expires_at has passed
+    #[serde(default)]
+    pub temporary: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// File in path launched with 'nvim -u', for single-file configs
+    pub entry: Option<String>,
 }
 
-// Backup Data Structure
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct BackupInfo {
-    pub name: String,
-    pub path: String,
+impl ConfigData {
+    // Tags are matched case-insensitively
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    // Single-file configs are launched with 'ncm run' instead of being symlinked
+    pub fn entry_file(&self) -> Option<PathBuf> {
+        self.entry.as_ref().map(|entry| PathBuf::from(&self.path).join(entry))
+    }
 }
 
-// Create BackupInfo new function
-impl BackupInfo {
-    pub fn new() -> BackupInfo {
-        BackupInfo {
-            name: String::new(),
-            path: String::new(),
-        }
+impl TsvRows for Configs {
+    fn tsv_header(&self) -> Vec<&'static str> {
+        vec!["name", "default", "path", "description", "tags", "data_path", "created_at", "last_loaded_at", "load_count", "remote", "git_ref", "pinned", "worktree_of", "temporary", "expires_at", "entry"]
+    }
+
+    fn tsv_rows(&self) -> Vec<Vec<String>> {
+        self.configs.iter().map(|cfg| vec![
+            cfg.name.to_string(),
+            (cfg.name == self.configs_default).to_string(),
+            cfg.path.to_string(),
+            cfg.description.clone().unwrap_or_default(),
+            cfg.tags.join(","),
+            cfg.data_path.clone().unwrap_or_default(),
+            cfg.created_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            cfg.last_loaded_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            cfg.load_count.to_string(),
+            cfg.remote.clone().unwrap_or_default(),
+            cfg.git_ref.clone().unwrap_or_default(),
+            cfg.pinned.clone().unwrap_or_default(),
+            cfg.worktree_of.clone().unwrap_or_default(),
+            cfg.temporary.to_string(),
+            cfg.expires_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            cfg.entry.clone().unwrap_or_default(),
+        ]).collect()
+    }
+}
+
+// --| Validate Name ----------------------------
+// Names are joined onto the ncm roots for config, data, cache and state
+// directories, so anything but a single plain path component is refused
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(part)), None) if part == name => Ok(()),
+        _ => Err(NcmError::InvalidArgs(format!("{} '{name}', {}", ERR_CONFIG_NAME, HINT_CONFIG_NAME))),
     }
 }
 
 // --| Load Configs -----------------------------
 // Load a configuration file by name. If name is not specified then the default configuration is used.
 pub(crate) fn load_configs(config_path: &str, config_name: &str) -> Result<ConfigData> {
-    let config_file = std::fs::read_to_string(config_path).expect("Failed to read file");
-
-    let mut target_config: String = String::new();
-    let mut configs: Configs = serde_json::from_str(&config_file)?;
-    let config_data: Vec<ConfigData> = configs.configs.clone();
-    let default_config = configs.configs_default.clone();
+    let mut configs = read_configs(config_path)?;
 
-    if config_name.is_empty() {
-        target_config = default_config;
+    let target_config = if config_name.is_empty() {
+        configs.configs_default.clone()
     } else {
-        target_config = config_name.to_string()
-    }
+        config_name.to_string()
+    };
 
-    let config_result = find_config(config_data, &target_config);
-    match config_result {
-        Ok(config) => {
-            configs.configs_default = config.name.to_string();
-            write_default(&configs, config_path)?;
-            Ok(config)
-        }
-        Err(_e) => Err(serde_json::Error::custom(format!("{} {}", ERR_CONFIGS_NAME, &target_config))),
-    }
+    let config = find_config(configs.configs.clone(), &target_config)?;
+    configs.configs_default = config.name.to_string();
+    write_config_to_disk(config_path, &configs)?;
+    Ok(config)
 }
 
 // --| Find Config ------------------------------
 // Using config_path as the json file location, and ConfigData struct as input, write the data to the json file.
-pub(crate) fn add_config(config_path: &str, config_data: ConfigData) -> Result<()> {
-    let config_file = std::fs::read_to_string(config_path).expect(ERR_READ_FILE);
-    let _name = &config_data.name.clone();
+pub(crate) fn add_config(config_path: &str, mut config_data: ConfigData) -> Result<()> {
+    let mut configs = read_configs(config_path)?;
+    config_data.created_at.get_or_insert_with(Utc::now);
 
     // --| Create .local data path for config ---
-    let data_path = &config_data.data_path.clone();
-    if data_path.is_some() {
-        let path = PathBuf::from(data_path.as_ref().unwrap());
+    if let Some(data_path) = &config_data.data_path {
+        let path = PathBuf::from(data_path);
 
         if !path.exists() {
-            info!("{}: {}", INFO_DIR_DATA, path.to_str().unwrap());
-            std::fs::create_dir_all(&path).expect(ERR_DIR_UCREATE);
+            info!("{}: {}", INFO_DIR_DATA, path.display());
+            std::fs::create_dir_all(&path)?;
         }
     } else { error!("{}", ERR_DIR_DATA); }
 
     // --| Create .cache data path for config ---
     if !cfg!(target_os = "windows") {
-        let cache_path = &config_data.cache_path;
-        if cache_path.is_some() {
-            let path = PathBuf::from(cache_path.as_ref().unwrap());
+        if let Some(cache_path) = &config_data.cache_path {
+            let path = PathBuf::from(cache_path);
 
             if !path.exists() {
-                info!("{}: {}", INFO_DIR_CACHE, path.to_str().unwrap());
-                std::fs::create_dir_all(&path).expect(ERR_DIR_UCREATE);
+                info!("{}: {}", INFO_DIR_CACHE, path.display());
+                std::fs::create_dir_all(&path)?;
             }
         } else { error!("{}", ERR_DIR_CACHE); }
     }
 
-    let mut configs: Configs = serde_json::from_str(&config_file)?;
     configs.configs.push(config_data);
-
-    let config_json = serde_json::to_string(&configs)?;
-
-    write_config_to_disk(config_path, config_json)
+    write_config_to_disk(config_path, &configs)
 }
 
 // --| Set Default ------------------------------
 // Using config_path as the json file location and return all current configs
-pub(crate) fn list_configs(config_path: &str) -> anyhow::Result<Configs> {
-    let config_file = std::fs::read_to_string(config_path).expect(ERR_READ_FILE);
+pub(crate) fn list_configs(config_path: &str) -> Result<Configs> {
+    read_configs(config_path)
+}
+
+// --| Get Config -------------------------------
+// Using config_path as the json file location, return the config matching
+// config_name without changing the current default
+pub(crate) fn get_config(config_path: &str, config_name: &str) -> Result<ConfigData> {
+    let configs = read_configs(config_path)?;
+    find_config(configs.configs, config_name)
+}
+
+// --| Update Config ----------------------------
+// Using config_path as the json file location, replace the stored entry
+// matching config_data.name with the values of config_data
+pub(crate) fn update_config(config_path: &str, config_data: ConfigData) -> Result<()> {
+    let mut configs = read_configs(config_path)?;
+    match configs.configs.iter_mut().find(|x| x.name == config_data.name) {
+        Some(config) => *config = config_data,
+        None => return Err(NcmError::ConfigNotFound(config_data.name)),
+    }
+
+    write_config_to_disk(config_path, &configs)
+}
+
+// --| Record Load ------------------------------
+// Update the usage metadata of the config matching config_name after it was loaded
+pub(crate) fn record_load(config_path: &str, config_name: &str) -> Result<ConfigData> {
+    let mut config = get_config(config_path, config_name)?;
+    config.last_loaded_at = Some(Utc::now());
+    config.load_count += 1;
+
+    update_config(config_path, config.clone())?;
+    Ok(config)
+}
+
+// --| Add Tags ---------------------------------
+// Add tags to the config matching config_name, ignoring ones it already has
+pub(crate) fn add_tags(config_path: &str, config_name: &str, tags: &[String]) -> Result<ConfigData> {
+    let mut config = get_config(config_path, config_name)?;
+
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !config.has_tag(tag) {
+            config.tags.push(tag.to_string());
+        }
+    }
+
+    update_config(config_path, config.clone())?;
+    Ok(config)
+}
+
+// --| Remove Tags ------------------------------
+// Remove tags from the config matching config_name
+pub(crate) fn remove_tags(config_path: &str, config_name: &str, tags: &[String]) -> Result<ConfigData> {
+    let mut config = get_config(config_path, config_name)?;
+    config.tags.retain(|t| !tags.iter().any(|x| x.trim().eq_ignore_ascii_case(t)));
 
-    let configs = serde_json::from_str::<Configs>(&config_file)?;
-    Ok(configs)
+    update_config(config_path, config.clone())?;
+    Ok(config)
 }
 
 // --| Remove Config ----------------------------
 // Using config_path as the json file location and config_name as the
 // name of the config to remove, remove the config from the json file
-pub(crate) fn remove_config(name: &Option<String>, config_path: &str) -> Result<()> {
-    let config_file = std::fs::read_to_string(config_path).expect(ERR_READ_FILE);
-    let config_name = name.as_ref().unwrap().to_string();
-
-    let mut configs: Configs = serde_json::from_str(&config_file)?;
+pub(crate) fn remove_config(config_name: &str, config_path: &str) -> Result<()> {
+    let mut configs = read_configs(config_path)?;
+    if !configs.configs.iter().any(|x| x.name == config_name) {
+        return Err(NcmError::ConfigNotFound(config_name.to_string()));
+    }
     configs.configs.retain(|x| x.name != config_name);
 
-    let config_json = serde_json::to_string(&configs)?;
-
-    write_config_to_disk(config_path, config_json)
+    write_config_to_disk(config_path, &configs)
 }
 
-pub(crate) fn create_symlink(nvim_path: PathBuf, new_config: PathBuf) -> anyhow::Result<()> {
+pub(crate) fn create_symlink(nvim_path: PathBuf, new_config: PathBuf) -> Result<()> {
+    if !new_config.exists() {
+        return Err(NcmError::invalid_path(ERR_CONFIGS_PATH, new_config));
+    }
     let new_config = new_config.canonicalize()?;
 
-    if !new_config.exists() {
-        return Err(anyhow!(format!("{}: {}", ERR_CONFIGS_PATH, new_config.to_str().unwrap())));
+    let symlink_err = |source: std::io::Error| NcmError::Symlink { path: nvim_path.clone(), target: new_config.clone(), source };
+
+    // --| A dangling symlink does not "exist", but still has to be removed
+    if nvim_path.is_symlink() && !nvim_path.exists() {
+        #[cfg(target_os = "windows")]
+        std::fs::remove_dir(&nvim_path).map_err(symlink_err)?;
+
+        #[cfg(not(target_os = "windows"))]
+        std::fs::remove_file(&nvim_path).map_err(symlink_err)?;
     }
 
-    match (&nvim_path.exists(), &nvim_path.is_dir(), true) {
-        (true, true, true) => std::fs::remove_dir_all(&nvim_path)?,
-        (true, false, true) => std::fs::remove_file(&nvim_path)?,
-        (true, _, false) => return Err(anyhow!(ERR_CONFIGS_WRITE)),
+    match (nvim_path.exists(), nvim_path.is_dir()) {
+        (true, true) => std::fs::remove_dir_all(&nvim_path).map_err(symlink_err)?,
+        (true, false) => std::fs::remove_file(&nvim_path).map_err(symlink_err)?,
         _ => {}
     };
 
     #[cfg(target_os = "linux")]
-    std::os::unix::fs::symlink(new_config, nvim_path)?;
+    std::os::unix::fs::symlink(&new_config, &nvim_path).map_err(symlink_err)?;
 
     #[cfg(target_os = "windows")]
-    std::os::windows::fs::symlink_dir(new_config, nvim_path)?;
+    std::os::windows::fs::symlink_dir(&new_config, &nvim_path).map_err(symlink_err)?;
 
     #[cfg(target_os = "macos")]
-    std::os::macos::fs::symlink(new_config, nvim_path)?;
-
+    std::os::macos::fs::symlink(&new_config, &nvim_path).map_err(symlink_err)?;
 
     Ok(())
 }
 
 // --| Helper Functions -------------------------
 // --|-------------------------------------------
+// Read and parse the registry
+fn read_configs(config_path: &str) -> Result<Configs> {
+    let config_file = std::fs::read_to_string(config_path)
+        .map_err(|source| NcmError::RegistryRead { path: PathBuf::from(config_path), source })?;
+
+    serde_json::from_str(&config_file)
+        .map_err(|source| NcmError::RegistryCorrupt { path: PathBuf::from(config_path), source })
+}
+
 // Write the configuration to disk
-fn write_config_to_disk(config_path: &str, config_json: String) -> Result<(), > {
-    if std::fs::write(config_path, config_json).is_ok() {
-        Ok(())
-    } else {
-        Err(serde_json::Error::custom(ERR_CONFIGS_WRITE))
-    }
+fn write_config_to_disk(config_path: &str, configs: &Configs) -> Result<()> {
+    let config_json = serde_json::to_string(configs)
+        .map_err(|source| NcmError::RegistryCorrupt { path: PathBuf::from(config_path), source })?;
+
+    std::fs::write(config_path, config_json)
+        .map_err(|source| NcmError::RegistryWrite { path: PathBuf::from(config_path), source })
 }
 
 // Check if a configuration exists by name
 fn find_config(configs: Vec<ConfigData>, config_name: &str) -> Result<ConfigData> {
-    if let Some(config) = configs.into_iter().find(|x| x.name == config_name) {
-        Ok(config)
-    } else {
-        Err(serde_json::Error::custom(format!("{} {config_name}", ERR_CONFIGS_NAME)))
-    }
-}
-
-// Write currently loaded configuration as the default configuration
-fn write_default(configs: &Configs, config_path: &str) -> Result<()> {
-    let config_json = serde_json::to_string(&configs)?;
-
-    if std::fs::write(config_path, config_json).is_ok() {
-        Ok(())
-    } else {
-        Err(serde_json::Error::custom(ERR_CONFIGS_WRITE))
-    }
+    configs.into_iter()
+        .find(|x| x.name == config_name)
+        .ok_or_else(|| NcmError::ConfigNotFound(config_name.to_string()))
 }
 
 // --| Tests ------------------------------------
@@ -284,6 +376,7 @@ mod tests {
             description: Some("test3".to_string()),
             data_path: Some(tmp_data_dir.join("config_three").to_str().unwrap().to_string()),
             cache_path: Some(tmp_data_dir.join("config_three").to_str().unwrap().to_string()),
+            ..Default::default()
         };
 
         // --| Add Configuration Test -----------
@@ -297,6 +390,8 @@ mod tests {
         assert_eq!(configs.configs.len(), 3);
         assert_eq!(configs.configs[2].name, "test3");
         assert_eq!(configs.configs[2].path, tmp_data_dir.join("config_three").to_str().unwrap());
+        assert!(configs.configs[2].created_at.is_some());
+        assert_eq!(configs.configs[2].load_count, 0);
 
         std::fs::remove_dir_all(tmp_nvim).unwrap();
     }
@@ -324,7 +419,7 @@ mod tests {
         let config_file = &tmp_config_dir.join("configs.json");
         println!("{}", config_file.to_str().unwrap());
 
-        let result = remove_config(&Some("default".to_string()), config_file.to_str().unwrap());
+        let result = remove_config("default", config_file.to_str().unwrap());
 
         assert!(result.is_ok());
 
@@ -338,6 +433,133 @@ mod tests {
         std::fs::remove_dir_all(tmp_nvim).unwrap();
     }
 
+    // --| Update Config ------------------------
+    #[test]
+    fn update_config_test() {
+        let mut tmp_nvim = std::env::temp_dir();
+        tmp_nvim.push("ncm_tmp_update");
+
+        let tmp_config_dir = tmp_nvim.join("config");
+        let tmp_data_dir = tmp_nvim.join("data");
+        let tmp_cache_dir = tmp_nvim.join("cache");
+
+        std::fs::create_dir_all(tmp_config_dir.clone()).unwrap();
+        std::fs::create_dir_all(tmp_data_dir.clone()).unwrap();
+        std::fs::create_dir_all(tmp_cache_dir.clone()).unwrap();
+
+        let _result = create_test_data(&tmp_config_dir, &tmp_data_dir, &tmp_cache_dir);
+
+        let config_file = &tmp_config_dir.join("configs.json");
+
+        let mut config = get_config(config_file.to_str().unwrap(), "test").unwrap();
+        config.description = Some("Updated description".to_string());
+        config.path = tmp_data_dir.join("config_one").to_str().unwrap().to_string();
+
+        // --| Update Configuration Test --------
+        let result = update_config(config_file.to_str().unwrap(), config);
+
+        assert!(result.is_ok());
+
+        let config_file_str = std::fs::read_to_string(config_file.to_str().unwrap()).expect("Failed to read file");
+        let configs: Configs = serde_json::from_str(&config_file_str).expect("Failed to parse json");
+
+        assert_eq!(configs.configs.len(), 2);
+        assert_eq!(configs.configs_default, "default");
+        assert_eq!(configs.configs[1].description, Some("Updated description".to_string()));
+        assert_eq!(configs.configs[1].path, tmp_data_dir.join("config_one").to_str().unwrap());
+
+        // --| Unknown names are rejected -------
+        let mut missing = configs.configs[1].clone();
+        missing.name = "missing".to_string();
+        assert!(update_config(config_file.to_str().unwrap(), missing).is_err());
+
+        std::fs::remove_dir_all(tmp_nvim).unwrap();
+    }
+
+    // --| Record Load ------------------------
+    #[test]
+    fn record_load_test() {
+        let mut tmp_nvim = std::env::temp_dir();
+        tmp_nvim.push("ncm_tmp_record_load");
+
+        let tmp_config_dir = tmp_nvim.join("config");
+        let tmp_data_dir = tmp_nvim.join("data");
+        let tmp_cache_dir = tmp_nvim.join("cache");
+
+        std::fs::create_dir_all(tmp_config_dir.clone()).unwrap();
+        std::fs::create_dir_all(tmp_data_dir.clone()).unwrap();
+        std::fs::create_dir_all(tmp_cache_dir.clone()).unwrap();
+
+        let _result = create_test_data(&tmp_config_dir, &tmp_data_dir, &tmp_cache_dir);
+
+        let config_file = tmp_config_dir.join("configs.json");
+        let config_file = config_file.to_str().unwrap();
+
+        assert!(get_config(config_file, "test").unwrap().last_loaded_at.is_none());
+
+        record_load(config_file, "test").unwrap();
+        let config = record_load(config_file, "test").unwrap();
+
+        assert_eq!(config.load_count, 2);
+        assert!(config.last_loaded_at.is_some());
+
+        let stored = get_config(config_file, "test").unwrap();
+        assert_eq!(stored.load_count, 2);
+        assert_eq!(stored.last_loaded_at, config.last_loaded_at);
+        assert_eq!(get_config(config_file, "default").unwrap().load_count, 0);
+
+        assert!(record_load(config_file, "missing").is_err());
+
+        std::fs::remove_dir_all(tmp_nvim).unwrap();
+    }
+
+    // --| Tags -------------------------------
+    #[test]
+    fn tags_test() {
+        let mut tmp_nvim = std::env::temp_dir();
+        tmp_nvim.push("ncm_tmp_tags");
+
+        let tmp_config_dir = tmp_nvim.join("config");
+        let tmp_data_dir = tmp_nvim.join("data");
+        let tmp_cache_dir = tmp_nvim.join("cache");
+
+        std::fs::create_dir_all(tmp_config_dir.clone()).unwrap();
+        std::fs::create_dir_all(tmp_data_dir.clone()).unwrap();
+        std::fs::create_dir_all(tmp_cache_dir.clone()).unwrap();
+
+        let _result = create_test_data(&tmp_config_dir, &tmp_data_dir, &tmp_cache_dir);
+
+        let config_file = tmp_config_dir.join("configs.json");
+        let config_file = config_file.to_str().unwrap();
+
+        // --| Adding skips duplicates ----------
+        let tags = vec!["work".to_string(), "Minimal".to_string(), "WORK".to_string()];
+        let config = add_tags(config_file, "test", &tags).unwrap();
+        assert_eq!(config.tags, vec!["work".to_string(), "Minimal".to_string()]);
+        assert!(config.has_tag("minimal"));
+
+        // --| Removing matches case-insensitively
+        let config = remove_tags(config_file, "test", &["minimal".to_string()]).unwrap();
+        assert_eq!(config.tags, vec!["work".to_string()]);
+
+        let stored = get_config(config_file, "test").unwrap();
+        assert_eq!(stored.tags, vec!["work".to_string()]);
+        assert!(get_config(config_file, "default").unwrap().tags.is_empty());
+
+        std::fs::remove_dir_all(tmp_nvim).unwrap();
+    }
+
+    // --| Validate Name ------------------------
+    #[test]
+    fn validate_name_test() {
+        for name in ["main", "my.config", "nvim-kickstart"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+        }
+        for name in ["", ".", "..", "../x", "a/b", "a/", "./a", "/tmp/x"] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+    }
+
     // --| Create Test Data ---------------------
     fn create_test_data(config_path: &Path, data_dir: &Path, cache_dir: &Path) -> Result<Configs> {
         let file_path = config_path.join("configs.json");
@@ -378,6 +600,7 @@ mod tests {
             description: Some("Default configuration".to_string()),
             data_path: Some(path_one_data.to_str().unwrap().to_string()),
             cache_path: Some(path_one_cache.to_str().unwrap().to_string()),
+            ..Default::default()
         });
         configs.configs.push(ConfigData {
             name: "test".to_string(),
@@ -385,6 +608,7 @@ mod tests {
             description: Some("Test configuration".to_string()),
             data_path: Some(path_two_data.to_str().unwrap().to_string()),
             cache_path: Some(path_two_cache.to_str().unwrap().to_string()),
+            ..Default::default()
         });
 
         let config_json = serde_json::to_string(&configs)?;
@@ -26,6 +26,9 @@ pub const APP_DATA_LOCAL_PATH: &str = "\\AppData\\Local";
 // --| Settings Keys -------------
 pub const NCM: &str = "ncm";
 pub const SETUP_COMPLETE: &str = "setup_complete";
+pub const SETUP_STAGE: &str = "setup_stage";
+pub const SETUP_NAME: &str = "setup_name";
+pub const SETUP_CONFIG_ROOT: &str = "setup_config_root";
 
 // --| Symbols and Notations -----
 pub const CHECK: &str = "✔";    
@@ -43,8 +46,27 @@ pub const CONFIG: &str = ".config";
 pub const WIN_DATA: &str = "Local";
 
 pub const ZIP: &str = "zip";
+pub const TSV_SEPARATOR: &str = "\t";
+pub const NONE: &str = "none";
 pub const MAIN: &str = "main";
 pub const NVIM: &str = "nvim";
+pub const GIT: &str = "git";
+pub const SHORT_COMMIT: usize = 7;
+pub const GIT_DIR: &str = ".git";
+pub const XDG_CONFIG: &str = "config";
+pub const XDG_DATA: &str = "data";
+pub const XDG_STATE: &str = "state";
+pub const XDG_CACHE: &str = "cache";
+pub const TEMPLATES: &str = "templates";
+pub const TRY_PREFIX: &str = "try";
+pub const TRY_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+pub const REPRO_LUA: &str = "repro.lua";
+pub const ENTRY_EXTENSIONS: [&str; 2] = ["lua", "vim"];
+pub const REPRO_ROOT: &str = ".repro";
+pub const REPRO_PLUGINS: &str = "{{plugins}}";
+pub const GIT_STATUS_CACHE: &str = "git-status.json";
+pub const GIT_STATUS_TTL: i64 = 30;
+pub const LOCKFILES: [&str; 4] = ["lazy-lock.json", "lazyvim.json", "rocks.toml", "nvim-pack-lock.json"];
 pub const INIT_LUA: &str = "init.lua";
 pub const INIT_VIM: &str = "init.vim";
 pub const NVIM_DATA: &str = "nvim-data";
@@ -54,8 +76,11 @@ pub const NCM_DATA: &str = "nvim-ncm";
 pub const NCM_DATA_WIN: &str = "nvim-ncm-data";
 
 pub const BACKUPS: &str = "backups";
+pub const TRASH: &str = "trash";
 pub const BACKUP_PATH: &str = "backup_path";
+pub const STRICT_PINS: &str = "strict_pins";
 pub const CONFIGS_FILE: &str = "configs.json";
+pub const HISTORY_FILE: &str = "history.json";
 pub const SETTINGS_FILE: &str = "settings.ini";
 pub const LOADING_SPINNER: &str = "Dots12";
 
@@ -65,6 +90,47 @@ pub const CLI_CURRENT_CONFIGS: &str = "Current Configurations";
 pub const CLI_TABLE_NAME: &str = "Name";
 pub const CLI_TABLE_PATH: &str = "Path";
 pub const CLI_TABLE_DESC: &str = "Description";
+pub const CLI_TABLE_TAGS: &str = "Tags";
+pub const CLI_TABLE_CREATED: &str = "Created";
+pub const CLI_TABLE_LAST_LOADED: &str = "Last Loaded";
+pub const CLI_TABLE_LOADS: &str = "Loads";
+pub const CLI_TABLE_PIN: &str = "Pin";
+pub const CLI_TABLE_BRANCH: &str = "Branch";
+pub const CLI_TABLE_SYNC: &str = "Upstream";
+pub const CLI_TABLE_DIRTY: &str = "Dirty";
+pub const CLI_GIT_SYNCED: &str = "=";
+pub const CLI_GIT_DIRTY: &str = "*";
+
+pub const CLI_SWITCH_HISTORY: &str = "Switch History";
+pub const CLI_STATUS: &str = "Current Status";
+pub const CLI_TABLE_KIND: &str = "Kind";
+pub const CLI_TABLE_TARGET: &str = "Target";
+pub const CLI_TABLE_CONFIG: &str = "Config";
+pub const CLI_BACKUPS: &str = "Backups";
+pub const CLI_TABLE_SIZE: &str = "Size";
+
+pub const OUTPUT_KIND_LIST: &str = "list";
+pub const OUTPUT_KIND_STATUS: &str = "status";
+pub const OUTPUT_KIND_BACKUPS: &str = "backups";
+pub const OUTPUT_KIND_DOCTOR: &str = "doctor";
+
+pub const STATUS_CONFIG: &str = "config";
+pub const STATUS_DATA: &str = "data";
+pub const STATUS_CACHE: &str = "cache";
+pub const STATUS_STATE: &str = "state";
+pub const STATUS_UNREGISTERED: &str = "(unregistered)";
+pub const STATUS_DANGLING: &str = "(dangling)";
+pub const STATUS_UNMANAGED: &str = "(not managed)";
+pub const STATUS_MISSING: &str = "(missing)";
+pub const STATUS_UNKNOWN: &str = "?";
+pub const STATUS_DRIFT_MARKER: &str = "*";
+pub const STATUS_DRIFT: &str = "Drift detected, not all paths belong to the default configuration";
+pub const CLI_TABLE_LOADED_AT: &str = "Loaded At";
+
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M";
+pub const TRASH_TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+pub const HISTORY_LIMIT: usize = 50;
+pub const PREVIOUS_CONFIG: &str = "-";
 
 // --| Default Values ------------
 pub const DEFAULT_CONFIG_DESC: &str = "Main Config";
@@ -78,11 +144,36 @@ pub const INFO_BACKUP_SELECT: &str = "Backup which configuration?";
 
 pub const INFO_CONFIGS_ADDED: &str = "Added new config";
 pub const INFO_CONFIGS_LOADING: &str = "Loading";
+pub const INFO_CONFIGS_UPDATED: &str = "Updated config";
+pub const INFO_CONFIGS_TAGS: &str = "Updated tags";
+pub const INFO_LOAD_SELECT: &str = "Load which configuration?";
+pub const INFO_REMOVE_SELECT: &str = "Remove which configuration?";
+pub const INFO_REMOVE_CONFIRM: &str = "Remove configuration";
+pub const INFO_REMOVE_CANCELLED: &str = "Removal cancelled";
+pub const INFO_CONFIGS_REMOVED: &str = "Removed config";
+pub const INFO_PURGE_DELETE: &str = "The following directories will be deleted for";
+pub const INFO_PURGE_TRASH: &str = "The following directories will be moved to the trash for";
+pub const INFO_PURGE_TOTAL: &str = "Total";
+pub const INFO_PURGE_CONFIRM: &str = "Remove and purge configuration";
+pub const INFO_PURGE_REMOVED: &str = "Removed";
+pub const INFO_PURGE_COMPLETE: &str = "Purge complete";
+
+pub const INFO_GC_NONE: &str = "No orphaned directories found";
+pub const INFO_GC_FOUND: &str = "Directories not referenced by any configuration";
+pub const INFO_GC_DRY_RUN: &str = "Dry run, nothing was removed. Run 'ncm gc --delete' to remove these directories";
+pub const INFO_GC_CONFIRM: &str = "Delete these directories?";
+pub const INFO_GC_CONFIRM_TRASH: &str = "Move these directories to the trash?";
+pub const INFO_GC_CANCELLED: &str = "Garbage collection cancelled";
 pub const INFO_CONFIG_PATH: &str = "Please enter a path in which to store your configurations";
 pub const INFO_CONFIG_NAME: &str = "Please enter a name for your configuration";
 pub const INFO_CONFIG_PATH_PLACEHOLDER: &str = "Press enter to use default";
 pub const INFO_CONFIG_NAME_PLACEHOLDER: &str = "Press enter to use default";
 
+pub const INFO_SET_DESCRIPTION: &str = "Description";
+pub const INFO_SET_PATH: &str = "Configuration path";
+pub const INFO_SET_DATA_PATH: &str = "Data path";
+pub const INFO_SET_RELOAD: &str = "This configuration is currently loaded, to apply the new paths run: ncm load";
+
 pub const INFO_DIR_CACHE: &str = "Creating cache directory: ";
 pub const INFO_DIR_DATA: &str = "Creating data directory: ";
 
@@ -93,12 +184,124 @@ pub const INFO_MOVING_DATA: &str = "Moving original data to";
 pub const INFO_MOVING_DATA_COMPLETE: &str = "Moving original data complete";
 pub const INFO_SELECT_ALL: &str = "all";
 pub const INFO_SETUP_COMPLETE: &str = "Setup complete!";
+pub const INFO_SETUP_ALREADY: &str = "Setup has already been completed";
+pub const INFO_BOOTSTRAP: &str = "No existing nvim configuration found, creating the ncm directories and an empty registry";
+pub const INFO_BOOTSTRAP_SELECT: &str = "Add a first configuration?";
+pub const INFO_BOOTSTRAP_LOCAL: &str = "From a local directory";
+pub const INFO_BOOTSTRAP_TEMPLATE: &str = "From a built-in template";
+pub const INFO_BOOTSTRAP_SKIP: &str = "Not now";
+pub const INFO_BOOTSTRAP_PATH: &str = "Configuration directory:";
+pub const INFO_BOOTSTRAP_ADD_LATER: &str = "Add a configuration with 'ncm add <name> <path>'";
+pub const INFO_TEMPLATE_SELECT: &str = "Which template?";
+pub const INFO_TEARDOWN_SELECT: &str = "Which configuration should be kept?";
+pub const INFO_TEARDOWN_RESTORE: &str = "Restoring to the nvim directories";
+pub const INFO_TEARDOWN_ARCHIVE: &str = "archive";
+pub const INFO_TEARDOWN_REMOVE: &str = "remove ";
+pub const INFO_TEARDOWN_DELETE: &str = "delete ";
+pub const INFO_TEARDOWN_CONFIRM: &str = "Remove ncm and restore this configuration?";
+pub const INFO_TEARDOWN_CANCELLED: &str = "Teardown cancelled";
+pub const INFO_TEARDOWN_COMPLETE: &str = "ncm removed, nvim now uses the real directory";
+pub const INFO_GIT_CLONING: &str = "Cloning";
+pub const INFO_GIT_CLONED: &str = "Clone complete";
+pub const INFO_UPDATE_DIRTY: &str = "skipped, it has uncommitted changes";
+pub const INFO_UPDATE_DETACHED: &str = "skipped, not on a branch (checked out at a tag or commit)";
+pub const INFO_UPDATE_NO_UPSTREAM: &str = "skipped, the branch has no upstream";
+pub const INFO_UPDATE_DIVERGED: &str = "skipped, local and remote branches have diverged";
+pub const INFO_UPDATE_CURRENT: &str = "already up to date";
+pub const INFO_UPDATE_COMMITS: &str = "new commits";
+pub const INFO_UPDATE_LOCKFILES: &str = "Plugin lockfile changed, run a plugin sync to match it";
+pub const INFO_UPDATE_NONE: &str = "No configurations added with --git to update";
+pub const INFO_UPDATE_PINNED: &str = "skipped, pinned to";
+pub const INFO_PINNED: &str = "Pinned";
+pub const INFO_UNPINNED: &str = "Unpinned";
+pub const INFO_PIN_AT: &str = "currently at";
+pub const HINT_PIN: &str = "check out the pinned commit, or run 'ncm pin' or 'ncm unpin'";
+pub const INFO_WORKTREE_CREATED: &str = "Created worktree for branch";
+pub const INFO_WORKTREE_REMOVED: &str = "Removed worktree";
+pub const INFO_TRY_DESCRIPTION: &str = "Trial of";
+pub const INFO_TRY_LAUNCH: &str = "Launching nvim with trial configuration";
+pub const INFO_RUN_LAUNCH: &str = "Launching nvim with configuration";
+pub const INFO_ENTRY_RUN: &str = "Single-file configuration, launch it with";
+pub const INFO_TRY_SELECT: &str = "Keep this configuration?";
+pub const INFO_TRY_KEEP: &str = "Keep it as a normal configuration";
+pub const INFO_TRY_DELETE: &str = "Delete it and everything it downloaded";
+pub const INFO_TRY_LEAVE: &str = "Leave it until it expires";
+pub const INFO_TRY_PROMOTED: &str = "Kept trial as configuration";
+pub const INFO_TRY_DELETED: &str = "Deleted trial";
+pub const INFO_GC_SKIP_LOADED: &str = "Skipping expired trial that is currently loaded";
+pub const INFO_TRY_LEFT: &str = "Trial left in place, 'ncm gc --delete' removes it after";
+pub const INFO_GC_EXPIRED: &str = "Expired trials";
+pub const INFO_TEMPLATE_USER: &str = "User template from";
+pub const INFO_TEMPLATE_NEXT: &str = "Load the new configuration with";
+pub const INFO_TEMPLATE_CREATED: &str = "Created configuration from template";
+pub const INFO_REPRO_DESCRIPTION: &str = "Repro of";
+pub const INFO_REPRO_CREATED: &str = "Created reproduction";
+pub const INFO_REPRO_COMMAND: &str = "Reproduce with";
+pub const INFO_REPRO_REMOVE: &str = "Remove it with";
+pub const INFO_SETUP_INTERRUPTED: &str = "A previous setup was interrupted after step";
+pub const INFO_SETUP_RESUME: &str = "Resume the setup";
+pub const INFO_SETUP_ROLLBACK: &str = "Roll back the setup";
+pub const INFO_SETUP_RESUMING: &str = "Resuming setup of";
+pub const INFO_SETUP_ROLLED_BACK: &str = "Setup has been rolled back";
+pub const INFO_SETUP_REGISTERED: &str = "Configuration is already registered";
+pub const INFO_ROLLBACK_RESTORED: &str = "Restored";
+pub const INFO_ROLLBACK_REMOVED: &str = "Removed";
+pub const INFO_SETUP_NO_BACKUP: &str = "Skipping the backup of the original configuration";
+pub const INFO_ASSUME_YES: &str = "(yes)";
+pub const INFO_SETUP_CONFIRM: &str = "Create Configuration Directory?";
 
 pub const DEBUG_CONFIG_VALIDATION_SUCCESS: &str = "init.lua or init.vim was found in the directory";
 
+// --| Doctor Checks -------------
+pub const CHECK_REGISTRY: &str = "Registry";
+pub const CHECK_DEFAULT: &str = "Default config";
+pub const CHECK_CONFIG_LINK: &str = "Config symlink";
+pub const CHECK_DATA_LINK: &str = "Data symlink";
+pub const CHECK_SETUP: &str = "Setup state";
+pub const CHECK_CONFIG_DIR: &str = "Config dir";
+pub const CHECK_DATA_DIR: &str = "Data dir";
+
+pub const DOCTOR_WARN: &str = "!";
+pub const DOCTOR_SUMMARY: &str = "checks";
+pub const DOCTOR_PASSED: &str = "passed";
+pub const DOCTOR_WARNINGS: &str = "warnings";
+pub const DOCTOR_FAILED: &str = "failed";
+pub const DOCTOR_REGISTRY_OK: &str = "configs.json is readable";
+pub const DOCTOR_DEFAULT_OK: &str = "Default configuration is";
+pub const DOCTOR_DEFAULT_EMPTY: &str = "No default configuration is set";
+pub const DOCTOR_DEFAULT_MISSING: &str = "Default configuration is not registered";
+pub const DOCTOR_LINK_REAL_DIR: &str = "Expected a symlink but found a real directory";
+pub const DOCTOR_LINK_MISSING: &str = "Symlink does not exist";
+pub const DOCTOR_LINK_DANGLING: &str = "Symlink target no longer exists";
+pub const DOCTOR_LINK_UNREGISTERED: &str = "Symlink points at a path no registered configuration uses";
+pub const DOCTOR_LINK_NOT_DEFAULT: &str = "Symlink points at";
+pub const DOCTOR_LINK_DEFAULT_IS: &str = "but the default configuration is";
+pub const DOCTOR_SETUP_UNREADABLE: &str = "Could not read setup_complete from";
+pub const DOCTOR_SETUP_INCOMPLETE_LINKED: &str = "settings.ini says setup_complete=false but nvim is already symlinked";
+pub const DOCTOR_SETUP_COMPLETE_UNLINKED: &str = "settings.ini says setup_complete=true but nvim is not symlinked";
+pub const DOCTOR_DATA_MISSING: &str = "Data directory does not exist";
+
+pub const REPAIR_SETTINGS: &str = "Rewrote settings.ini";
+pub const REPAIR_SYMLINK: &str = "Re-pointed symlink";
+pub const REPAIR_DATA_DIR: &str = "Created missing data directory";
+pub const REPAIR_MISSING_CONFIG: &str = "Configuration directory no longer exists for";
+pub const REPAIR_NOTHING: &str = "Nothing to repair";
+pub const REPAIR_COMPLETE: &str = "Repair complete, run 'ncm doctor' to verify";
+
+pub const HINT_NAME: &str = "<name>";
+pub const HINT_REGISTRY: &str = "Fix or restore configs.json in the ncm-rs config directory";
+pub const HINT_LOAD: &str = "Run 'ncm load <name>' to select a configuration";
+pub const HINT_SETUP_RESUME: &str = "Run 'ncm setup' to resume it, or 'ncm setup --rollback' to undo it";
+pub const HINT_REAL_DIR: &str = "Move the directory into ncm with 'ncm add', or remove it and run 'ncm load <name>'";
+pub const HINT_REPAIR: &str = "Run 'ncm repair' or 'ncm load <name>'";
+pub const HINT_SET_PATH: &str = "Run 'ncm set <name> --path <path>' or 'ncm remove <name>'";
+pub const HINT_SET_DATA_PATH: &str = "Run 'ncm set <name> --data-path <path>'";
+
 // --| Help Messages -------------
 pub const HELP_BACKUP_MSG: &str = "This will create a compressed backup of your original config (always best to have a backup), and relocate it to a new directory.";
 
+pub const HELP_SELECT_CONFIG: &str = "Type to fuzzy filter by name, tag, description or path. ✔ marks the current default.";
+
 pub const HELP_CONFIG_PATH: &str = "This is the path in which your configurations will be stored. If the directory does not exist, it will be created.";
 pub const HELP_CONFIG_NAME: &str = "This will be used to identify your configuration when loading it.";
 
@@ -113,6 +316,9 @@ pub const ERR_CONFIGS_ADD: &str = "Error adding new config";
 pub const ERR_CONFIGS_LIST: &str = "Error listing configs";
 pub const ERR_CONFIGS_LOAD: &str = "Error loading configs";
 pub const ERR_CONFIGS_NAME: &str = "No configuration found with name";
+pub const ERR_CONFIGS_TAG: &str = "No configuration found with tag";
+pub const ERR_CONFIGS_EMPTY: &str = "No configurations have been added yet";
+pub const ERR_CONFIGS_USAGE: &str = "Could not record configuration usage";
 pub const ERR_CONFIGS_PATH: &str = "Configuration path not found";
 pub const ERR_CONFIGS_PARSE: &str = "Could not parse configurations from configs.json";
 pub const ERR_CONFIGS_READ: &str = "Could not read configurations from configs.json";
@@ -145,6 +351,16 @@ pub const ERR_NVIM_NOT_FOUND_WIN_DATA: &str = "Could not find nvim data in the e
 pub const ERR_NVIM_NOT_FOUND_LINUX: &str = "Could not find nvim configuration in the expected location. ($XDG_CONFIG_HOME/nvim)";
 pub const ERR_NVIM_NOT_FOUND_LINUX_NO_XDG: &str = "It appears that XDG_CONFIG_HOME is not set";
 
+pub const ERR_HISTORY_EMPTY: &str = "No previously loaded configuration to switch back to";
+pub const ERR_HISTORY_PARSE: &str = "Could not parse switch history from history.json";
+pub const ERR_HISTORY_WRITE: &str = "Failed to write switch history to disk";
+
+pub const ERR_PURGE_LOADED: &str = "Refusing to purge the currently loaded configuration";
+pub const ERR_PURGE_LOAD_OTHER: &str = "load another configuration first";
+pub const ERR_CLEANUP_REMOVE: &str = "Could not remove";
+
+pub const ERR_REPAIR_SYMLINK: &str = "Could not repair symlinks";
+
 pub const ERR_READ_FILE: &str = "Failed to read file";
 pub const ERR_RUN_SETUP: &str = "Please run 'ncm setup' to configure NCM, or follow the manual setup instructions at https://github.com/instance-id/ncm-rs";
 
@@ -153,3 +369,46 @@ pub const ERR_SETTINGS_READ: &str = "Could not read setup_pending from settings.
 pub const ERR_SETTINGS_UREAD: &str = "Unable to read settings file";
 pub const ERR_SETTINGS_UWRITE: &str = "Unable to write settings file";
 pub const ERR_SYMLINK_CREATE: &str = "Error creating symlink";
+pub const ERR_CHECKS_FAILED: &str = "Health checks failed:";
+pub const ERR_PROMPT: &str = "Could not prompt for input";
+pub const ERR_ABORTED: &str = "Aborted";
+pub const ERR_NO_TERMINAL: &str = "No terminal is attached to answer the prompt";
+pub const HINT_NON_INTERACTIVE: &str = "pass the value as an argument, --yes to confirm, or run 'ncm setup --non-interactive'";
+pub const ERR_PATH_UTF8: &str = "Path is not valid UTF-8";
+pub const ERR_MOVE: &str = "Could not move";
+pub const ERR_TEARDOWN_NOT_LINK: &str = "Not a symlink managed by ncm, refusing to overwrite";
+pub const ERR_TEARDOWN_COPY: &str = "Could not copy";
+pub const ERR_TEARDOWN_DISCARD: &str = "Teardown would delete the configurations";
+pub const HINT_TEARDOWN_DISCARD: &str = "pass --archive <dir> to keep a copy or --discard-others to delete them";
+pub const ERR_TEARDOWN_ENTRY: &str = "Single-file configurations can not replace the nvim directory";
+pub const ERR_ENTRY_EXTENSION: &str = "Single-file configurations must be a .lua or .vim file";
+pub const ERR_ENTRY_LOAD: &str = "Single-file configurations are launched with 'ncm run' instead of loaded";
+pub const ERR_GIT: &str = "Git error";
+pub const ERR_GIT_RUN: &str = "Could not run git, is it installed and on PATH?";
+pub const ERR_UPDATE_NOT_GIT: &str = "was not added from git, nothing to update";
+pub const ERR_UPDATE_FAILED: &str = "configurations could not be updated";
+pub const ERR_GIT_REVISION: &str = "Unknown revision";
+pub const ERR_GIT_REF: &str = "Invalid git ref";
+pub const ERR_PIN_DRIFT: &str = "is not at its pinned commit";
+pub const ERR_NOT_PINNED: &str = "is not pinned";
+pub const ERR_WORKTREE_NOT_GIT: &str = "Configuration directory is not a git repository";
+pub const ERR_TRY_SOURCE: &str = "Not a configuration directory or git url";
+pub const ERR_TRY_TTL: &str = "Invalid ttl";
+pub const HINT_TRY_TTL: &str = "use a positive number followed by m, h, d or w, e.g. 12h";
+pub const ERR_TRY_TTL_RANGE: &str = "ttl is out of range";
+pub const ERR_NVIM_RUN: &str = "Could not run nvim, is it installed and on PATH?";
+pub const ERR_INVALID_ARGS: &str = "Invalid arguments";
+pub const ERR_LOG_LEVEL: &str = "Invalid NCM_LOG_LEVEL";
+pub const HINT_LOG_LEVEL: &str = "using Info, expected one of Off, Error, Warn, Info, Debug or Trace";
+pub const ERR_ADD_SOURCE: &str = "ncm add needs a path, --git <url> or --worktree";
+pub const ERR_GIT_DESTINATION: &str = "A directory with this name already exists in the ncm configs directory";
+pub const ERR_CONFIGS_EXISTS: &str = "A configuration already exists with name";
+pub const ERR_CONFIG_NAME: &str = "Invalid configuration name";
+pub const HINT_CONFIG_NAME: &str = "use a single directory name without '/' or '..'";
+pub const ERR_TEMPLATE_NAME: &str = "No template found with name";
+pub const ERR_TEMPLATE_DESTINATION: &str = "Destination already exists and is not empty";
+pub const ERR_REPRO_SPEC: &str = "Invalid plugin spec";
+pub const HINT_REPRO_SPEC: &str = "use owner/repo or a git url";
+pub const ERR_ROLLBACK_CONFIG: &str = "Could not find the original configuration to restore";
+pub const ERR_SETUP_INTERRUPTED: &str = "A previous setup was interrupted, run 'ncm setup' to resume it or 'ncm setup --rollback' to undo it";
+pub const ERR_NO_ROLLBACK: &str = "There is no interrupted setup to roll back";
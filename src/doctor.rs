@@ -0,0 +1,295 @@
+use std::path::Path;
+use serde::Serialize;
+
+use crate::configs;
+use crate::setup;
+use crate::constants::*;
+use crate::settings::Settings;
+use crate::configs::{ConfigData, Configs};
+use crate::output::TsvRows;
+use crate::status::{self, LinkState, PathKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+        }
+    }
+}
+
+// Result of a single health check, with a hint on how to fix it
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &str, message: String) -> CheckResult {
+        CheckResult { name: name.to_string(), status: CheckStatus::Pass, message, hint: None }
+    }
+
+    fn warn(name: &str, message: String, hint: &str) -> CheckResult {
+        CheckResult { name: name.to_string(), status: CheckStatus::Warn, message, hint: Some(hint.to_string()) }
+    }
+
+    fn fail(name: &str, message: String, hint: &str) -> CheckResult {
+        CheckResult { name: name.to_string(), status: CheckStatus::Fail, message, hint: Some(hint.to_string()) }
+    }
+}
+
+// All check results along with a count per status
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    pub fn new(checks: Vec<CheckResult>) -> DoctorReport {
+        let count = |status: CheckStatus| checks.iter().filter(|c| c.status == status).count();
+        DoctorReport {
+            passed: count(CheckStatus::Pass),
+            warnings: count(CheckStatus::Warn),
+            failed: count(CheckStatus::Fail),
+            checks,
+        }
+    }
+}
+
+impl TsvRows for DoctorReport {
+    fn tsv_header(&self) -> Vec<&'static str> {
+        vec!["status", "check", "message", "hint"]
+    }
+
+    fn tsv_rows(&self) -> Vec<Vec<String>> {
+        self.checks.iter().map(|c| vec![
+            c.status.label().to_string(),
+            c.name.to_string(),
+            c.message.to_string(),
+            c.hint.clone().unwrap_or_default(),
+        ]).collect()
+    }
+}
+
+// --| Run Checks -------------------------------
+// Check the registry, the nvim symlinks and the per-config directories
+pub(crate) fn run_checks(settings: &Settings) -> Vec<CheckResult> {
+    let mut results: Vec<CheckResult> = Vec::new();
+
+    // --| Registry --------------------------
+    let cfgs = match configs::list_configs(&settings.configs_path.to_string_lossy()) {
+        Ok(cfgs) => {
+            results.push(CheckResult::pass(CHECK_REGISTRY, format!("{} ({})", DOCTOR_REGISTRY_OK, cfgs.configs.len())));
+            cfgs
+        }
+        Err(e) => {
+            results.push(CheckResult::fail(CHECK_REGISTRY, e.to_string(), HINT_REGISTRY));
+            return results;
+        }
+    };
+
+    results.push(check_default(&cfgs));
+
+    // --| Symlinks --------------------------
+    results.push(check_link(CHECK_CONFIG_LINK, PathKind::Config, &settings.nvim_path, &cfgs, settings));
+    results.push(check_link(CHECK_DATA_LINK, PathKind::Data, &settings.data_path, &cfgs, settings));
+
+    results.push(check_setup_state(settings));
+
+    // --| Per-config directories ------------
+    for cfg in &cfgs.configs {
+        results.push(check_config_dir(cfg));
+        results.push(check_data_dir(cfg));
+    }
+
+    results
+}
+
+// The stored default must name a registered config
+fn check_default(cfgs: &Configs) -> CheckResult {
+    if cfgs.configs_default.is_empty() {
+        return CheckResult::warn(CHECK_DEFAULT, DOCTOR_DEFAULT_EMPTY.to_string(), HINT_LOAD);
+    }
+
+    if cfgs.configs.iter().any(|cfg| cfg.name == cfgs.configs_default) {
+        CheckResult::pass(CHECK_DEFAULT, format!("{} '{}'", DOCTOR_DEFAULT_OK, cfgs.configs_default))
+    } else {
+        CheckResult::fail(CHECK_DEFAULT, format!("{} '{}'", DOCTOR_DEFAULT_MISSING, cfgs.configs_default), HINT_LOAD)
+    }
+}
+
+// An nvim path must be a symlink pointing at the matching path of a registered config
+fn check_link(name: &str, kind: PathKind, link: &Path, cfgs: &Configs, settings: &Settings) -> CheckResult {
+    let link_status = status::resolve_link(kind, link, cfgs, settings);
+    let target = link_status.target.unwrap_or_default();
+
+    match (link_status.state, link_status.owner) {
+        (LinkState::Unmanaged, _) => CheckResult::fail(name, format!("{}: {:?}", DOCTOR_LINK_REAL_DIR, link), HINT_REAL_DIR),
+        (LinkState::Missing, _) => CheckResult::warn(name, format!("{}: {:?}", DOCTOR_LINK_MISSING, link), HINT_LOAD),
+        (LinkState::Dangling, _) => CheckResult::fail(name, format!("{}: {:?} -> {:?}", DOCTOR_LINK_DANGLING, link, target), HINT_REPAIR),
+        (LinkState::Linked, Some(owner)) if owner == cfgs.configs_default => {
+            CheckResult::pass(name, format!("{:?} -> {:?} ('{}')", link, target, owner))
+        }
+        (LinkState::Linked, Some(owner)) => {
+            CheckResult::warn(name, format!("{} '{}' {} '{}'", DOCTOR_LINK_NOT_DEFAULT, owner, DOCTOR_LINK_DEFAULT_IS, cfgs.configs_default), HINT_REPAIR)
+        }
+        _ => CheckResult::fail(name, format!("{}: {:?} -> {:?}", DOCTOR_LINK_UNREGISTERED, link, target), HINT_REPAIR),
+    }
+}
+
+// settings.ini should agree with what is on disk
+fn check_setup_state(settings: &Settings) -> CheckResult {
+    if let Some(progress) = setup::read_progress(settings) {
+        return CheckResult::warn(CHECK_SETUP, format!("{} '{}'", INFO_SETUP_INTERRUPTED, progress.stage.label()), HINT_SETUP_RESUME);
+    }
+
+    let setup_complete = settings.settings.getbool(NCM, SETUP_COMPLETE).ok().flatten();
+    let linked = settings.nvim_path.is_symlink();
+
+    match (setup_complete, linked) {
+        (None, _) => CheckResult::fail(CHECK_SETUP, format!("{}: {:?}", DOCTOR_SETUP_UNREADABLE, settings.settings_path), HINT_REPAIR),
+        (Some(false), true) => CheckResult::warn(CHECK_SETUP, DOCTOR_SETUP_INCOMPLETE_LINKED.to_string(), HINT_REPAIR),
+        (Some(true), false) => CheckResult::warn(CHECK_SETUP, DOCTOR_SETUP_COMPLETE_UNLINKED.to_string(), HINT_LOAD),
+        (Some(complete), _) => CheckResult::pass(CHECK_SETUP, format!("{}={complete}", SETUP_COMPLETE)),
+    }
+}
+
+fn check_config_dir(cfg: &ConfigData) -> CheckResult {
+    let name = format!("{} '{}'", CHECK_CONFIG_DIR, cfg.name);
+    let path = Path::new(&cfg.path);
+
+    let hint = HINT_SET_PATH.replace(HINT_NAME, &cfg.name);
+
+    if let Some(file) = cfg.entry_file() {
+        if file.is_file() {
+            CheckResult::pass(&name, format!("{:?}", file))
+        } else {
+            CheckResult::fail(&name, format!("{}: {:?}", ERR_CONFIGS_PATH, file), &hint)
+        }
+    } else if !path.exists() {
+        CheckResult::fail(&name, format!("{}: {:?}", ERR_CONFIGS_PATH, path), &hint)
+    } else if !path.join(INIT_LUA).exists() && !path.join(INIT_VIM).exists() {
+        CheckResult::warn(&name, format!("{}: {:?}", ERR_DIR_CONFIG_VERIFICATION, path), &hint)
+    } else {
+        CheckResult::pass(&name, format!("{:?}", path))
+    }
+}
+
+fn check_data_dir(cfg: &ConfigData) -> CheckResult {
+    let name = format!("{} '{}'", CHECK_DATA_DIR, cfg.name);
+
+    match &cfg.data_path {
+        None => CheckResult::warn(&name, ERR_DIR_DATA.to_string(), &HINT_SET_DATA_PATH.replace(HINT_NAME, &cfg.name)),
+        Some(data_path) if !Path::new(data_path).is_dir() => {
+            CheckResult::warn(&name, format!("{}: {:?}", DOCTOR_DATA_MISSING, data_path), HINT_REPAIR)
+        }
+        Some(data_path) => CheckResult::pass(&name, format!("{:?}", data_path)),
+    }
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use pretty_assertions::assert_eq;
+
+    fn test_settings(root: &Path) -> Settings {
+        let mut settings = Settings {
+            nvim_path: root.join("nvim"),
+            data_path: root.join("nvim-data"),
+            configs_path: root.join(CONFIGS_FILE),
+            settings_path: root.join(SETTINGS_FILE),
+            ..Default::default()
+        };
+        settings.settings.read(String::from("[ncm]\nsetup_complete = true")).unwrap();
+        settings
+    }
+
+    fn write_configs(settings: &Settings, configs: &Configs) {
+        std::fs::write(&settings.configs_path, serde_json::to_string(configs).unwrap()).unwrap();
+    }
+
+    fn status_of(results: &[CheckResult], name: &str) -> CheckStatus {
+        results.iter().find(|r| r.name == name).unwrap().status
+    }
+
+    // Test a healthy installation, then break the symlink and the default
+    #[test]
+    fn test_run_checks() {
+        let dir = tempdir().unwrap();
+        let settings = test_settings(dir.path());
+
+        let config_dir = dir.path().join("configs").join("main");
+        let data_dir = dir.path().join("data").join("main");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::write(config_dir.join(INIT_LUA), "").unwrap();
+
+        let mut cfgs = Configs {
+            configs_default: "main".to_string(),
+            configs: vec![ConfigData {
+                name: "main".to_string(),
+                path: config_dir.to_str().unwrap().to_string(),
+                data_path: Some(data_dir.to_str().unwrap().to_string()),
+                ..Default::default()
+            }],
+        };
+        write_configs(&settings, &cfgs);
+
+        configs::create_symlink(settings.nvim_path.clone(), config_dir.clone()).unwrap();
+        configs::create_symlink(settings.data_path.clone(), data_dir.clone()).unwrap();
+
+        let results = run_checks(&settings);
+        assert!(results.iter().all(|r| r.status == CheckStatus::Pass), "{:?}", results);
+
+        // --| Default names a removed config ---
+        cfgs.configs_default = "removed".to_string();
+        write_configs(&settings, &cfgs);
+
+        let results = run_checks(&settings);
+        assert_eq!(status_of(&results, CHECK_DEFAULT), CheckStatus::Fail);
+        assert_eq!(status_of(&results, CHECK_CONFIG_LINK), CheckStatus::Warn);
+
+        // --| Symlink target was deleted -------
+        std::fs::remove_dir_all(&config_dir).unwrap();
+
+        let results = run_checks(&settings);
+        assert_eq!(status_of(&results, CHECK_CONFIG_LINK), CheckStatus::Fail);
+        assert_eq!(status_of(&results, &format!("{} 'main'", CHECK_CONFIG_DIR)), CheckStatus::Fail);
+
+        dir.close().unwrap();
+    }
+
+    // Test that a real nvim directory in place of the symlink fails
+    #[test]
+    fn test_real_directory() {
+        let dir = tempdir().unwrap();
+        let settings = test_settings(dir.path());
+
+        std::fs::create_dir_all(&settings.nvim_path).unwrap();
+        write_configs(&settings, &Configs { configs_default: String::new(), configs: Vec::new() });
+
+        let results = run_checks(&settings);
+        assert_eq!(status_of(&results, CHECK_CONFIG_LINK), CheckStatus::Fail);
+        assert_eq!(status_of(&results, CHECK_DEFAULT), CheckStatus::Warn);
+        assert_eq!(status_of(&results, CHECK_SETUP), CheckStatus::Warn);
+
+        dir.close().unwrap();
+    }
+}
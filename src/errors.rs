@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use inquire::InquireError;
+use thiserror::Error;
+
+use crate::constants::*;
+
+pub type Result<T, E = NcmError> = std::result::Result<T, E>;
+
+// --| Exit Codes -------------------------------
+// Documented in the README, do not renumber
+pub const EXIT_GENERAL: i32 = 1;
+pub const EXIT_INVALID_ARGS: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_INVALID_PATH: i32 = 4;
+pub const EXIT_SYMLINK: i32 = 5;
+pub const EXIT_REGISTRY: i32 = 6;
+pub const EXIT_SETUP: i32 = 7;
+pub const EXIT_BACKUP: i32 = 8;
+pub const EXIT_IO: i32 = 9;
+pub const EXIT_PROMPT: i32 = 10;
+pub const EXIT_CHECKS_FAILED: i32 = 11;
+pub const EXIT_IN_USE: i32 = 12;
+pub const EXIT_GIT: i32 = 13;
+pub const EXIT_ABORTED: i32 = 130;
+
+#[derive(Debug, Error)]
+pub enum NcmError {
+    #[error("{} {0}", ERR_CONFIGS_NAME)]
+    ConfigNotFound(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{}: {0}", ERR_INVALID_ARGS)]
+    InvalidArgs(String),
+
+    #[error("{} '{0}', {}", ERR_PURGE_LOADED, ERR_PURGE_LOAD_OTHER)]
+    ConfigInUse(String),
+
+    #[error("{reason}: {path:?}")]
+    InvalidPath { reason: &'static str, path: PathBuf },
+
+    #[error("{}: {path:?} -> {target:?}: {source}", ERR_SYMLINK_CREATE)]
+    Symlink { path: PathBuf, target: PathBuf, source: std::io::Error },
+
+    #[error("{}: {path:?}: {source}", ERR_CONFIGS_READ)]
+    RegistryRead { path: PathBuf, source: std::io::Error },
+
+    #[error("{}: {path:?}: {source}", ERR_CONFIGS_PARSE)]
+    RegistryCorrupt { path: PathBuf, source: serde_json::Error },
+
+    #[error("{}: {path:?}: {source}", ERR_CONFIGS_WRITE)]
+    RegistryWrite { path: PathBuf, source: std::io::Error },
+
+    #[error("{0}")]
+    Settings(String),
+
+    #[error("{0}")]
+    Setup(String),
+
+    #[error("{0}")]
+    Backup(String),
+
+    #[error("{} {0}", ERR_CHECKS_FAILED)]
+    ChecksFailed(usize),
+
+    #[error("{}: {0}", ERR_GIT)]
+    Git(String),
+
+    #[error("{}: {0}", ERR_PROMPT)]
+    Prompt(InquireError),
+
+    #[error("{} '{0}', {}", ERR_NO_TERMINAL, HINT_NON_INTERACTIVE)]
+    NotInteractive(String),
+
+    #[error("{}", ERR_ABORTED)]
+    Aborted,
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl NcmError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            NcmError::ConfigNotFound(_) | NcmError::NotFound(_) => EXIT_NOT_FOUND,
+            NcmError::InvalidArgs(_) => EXIT_INVALID_ARGS,
+            NcmError::ConfigInUse(_) => EXIT_IN_USE,
+            NcmError::InvalidPath { .. } => EXIT_INVALID_PATH,
+            NcmError::Symlink { .. } => EXIT_SYMLINK,
+            NcmError::RegistryRead { .. } | NcmError::RegistryCorrupt { .. } | NcmError::RegistryWrite { .. } => EXIT_REGISTRY,
+            NcmError::Settings(_) | NcmError::Setup(_) => EXIT_SETUP,
+            NcmError::Backup(_) => EXIT_BACKUP,
+            NcmError::ChecksFailed(_) => EXIT_CHECKS_FAILED,
+            NcmError::Git(_) => EXIT_GIT,
+            NcmError::Prompt(_) | NcmError::NotInteractive(_) => EXIT_PROMPT,
+            NcmError::Aborted => EXIT_ABORTED,
+            NcmError::Io(_) => EXIT_IO,
+            NcmError::Other(_) => EXIT_GENERAL,
+        }
+    }
+
+    pub fn invalid_path(reason: &'static str, path: impl Into<PathBuf>) -> NcmError {
+        NcmError::InvalidPath { reason, path: path.into() }
+    }
+}
+
+// Escape or ctrl-c at a prompt is the user backing out, not a failure
+impl From<InquireError> for NcmError {
+    fn from(e: InquireError) -> Self {
+        match e {
+            InquireError::OperationCanceled | InquireError::OperationInterrupted => NcmError::Aborted,
+            e => NcmError::Prompt(e),
+        }
+    }
+}
+
+// Helper modules return anyhow errors which may already wrap an NcmError
+impl From<anyhow::Error> for NcmError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<NcmError>() {
+            Ok(e) => e,
+            Err(e) => NcmError::Other(e),
+        }
+    }
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Test that wrapped errors keep the exit code of the original variant
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(NcmError::ConfigNotFound("a".to_string()).exit_code(), EXIT_NOT_FOUND);
+        assert_eq!(NcmError::from(InquireError::OperationCanceled).exit_code(), EXIT_ABORTED);
+
+        let wrapped = anyhow::Error::new(NcmError::invalid_path(ERR_CONFIGS_PATH, "/tmp"));
+        assert_eq!(NcmError::from(wrapped).exit_code(), EXIT_INVALID_PATH);
+        assert_eq!(NcmError::from(anyhow::anyhow!("other")).exit_code(), EXIT_GENERAL);
+    }
+}
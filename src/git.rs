@@ -0,0 +1,447 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::*;
+
+// --| Run Git ----------------------------------
+// Run git in `dir` (when given) and return its trimmed stdout. Credential
+// prompts are disabled so a private remote fails instead of hanging.
+fn git(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new(GIT);
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+
+    let output = command.args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| anyhow!("{}: {e}", ERR_GIT_RUN))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} {}: {}", GIT, args.join(" "), stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// --| Clone ------------------------------------
+// Clone url into destination and check out git_ref (branch, tag or commit) if given.
+// The url follows '--' and refs starting with '-' are refused, so neither is read as an option.
+pub(crate) fn clone(url: &str, git_ref: Option<&str>, destination: &Path) -> Result<()> {
+    let destination_str = destination.to_str().ok_or_else(|| anyhow!("{}: {:?}", ERR_PATH_UTF8, destination))?;
+    if let Some(git_ref) = git_ref {
+        check_ref(git_ref)?;
+    }
+    git(None, &["clone", "--quiet", "--", url, destination_str])?;
+
+    if let Some(git_ref) = git_ref {
+        if let Err(e) = git(Some(destination), &["checkout", "--quiet", git_ref, "--"]) {
+            let _ = std::fs::remove_dir_all(destination);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+// A branch, tag or commit given on the command line
+pub(crate) fn check_ref(git_ref: &str) -> Result<()> {
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err(anyhow!("{} '{git_ref}'", ERR_GIT_REF));
+    }
+    Ok(())
+}
+
+// The checked out branch, or None when HEAD is detached (e.g. a tag or commit)
+pub(crate) fn current_branch(path: &Path) -> Result<Option<String>> {
+    let branch = git(Some(path), &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    Ok(if branch == "HEAD" { None } else { Some(branch) })
+}
+
+// --| Worktrees --------------------------------
+// Check out branch of the repository at repo into a new linked worktree at destination.
+// A branch that only exists on origin is tracked, an unknown one is created from HEAD.
+pub(crate) fn add_worktree(repo: &Path, branch: &str, destination: &Path) -> Result<()> {
+    let destination_str = destination.to_str().ok_or_else(|| anyhow!("{}: {:?}", ERR_PATH_UTF8, destination))?;
+    let local = format!("refs/heads/{branch}");
+    let remote = format!("refs/remotes/origin/{branch}");
+
+    if git(Some(repo), &["rev-parse", "--verify", "--quiet", &local]).is_ok() {
+        git(Some(repo), &["worktree", "add", "--quiet", destination_str, branch])?;
+    } else if git(Some(repo), &["rev-parse", "--verify", "--quiet", &remote]).is_ok() {
+        git(Some(repo), &["worktree", "add", "--quiet", "--track", "-b", branch, destination_str, &remote])?;
+    } else {
+        git(Some(repo), &["worktree", "add", "--quiet", "-b", branch, destination_str])?;
+    }
+    Ok(())
+}
+
+// The shared .git directory of the repository path belongs to, which for a
+// linked worktree is the one of the repository it was created from
+pub(crate) fn common_dir(path: &Path) -> Result<PathBuf> {
+    let common = git(Some(path), &["rev-parse", "--path-format=absolute", "--git-common-dir"])?;
+    Ok(PathBuf::from(common))
+}
+
+// Remove a linked worktree (refused by git when it has local changes) and
+// prune the bookkeeping of worktrees that no longer exist
+pub(crate) fn remove_worktree(common_dir: &Path, worktree: &Path) -> Result<()> {
+    let git_dir = common_dir.to_str().ok_or_else(|| anyhow!("{}: {:?}", ERR_PATH_UTF8, common_dir))?;
+
+    if worktree.exists() {
+        let worktree_str = worktree.to_str().ok_or_else(|| anyhow!("{}: {:?}", ERR_PATH_UTF8, worktree))?;
+        git(None, &["--git-dir", git_dir, "worktree", "remove", worktree_str])?;
+    }
+    git(None, &["--git-dir", git_dir, "worktree", "prune"])?;
+    Ok(())
+}
+
+// --| Revisions --------------------------------
+// Full commit id of rev (branch, tag, commit or HEAD)
+pub(crate) fn resolve(path: &Path, rev: &str) -> Result<String> {
+    git(Some(path), &["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .map_err(|_| anyhow!("{} '{rev}'", ERR_GIT_REVISION))
+}
+
+pub(crate) fn head(path: &Path) -> Result<String> {
+    resolve(path, "HEAD")
+}
+
+// Abbreviated commit id for display
+pub(crate) fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(SHORT_COMMIT)]
+}
+
+// --| Update -----------------------------------
+// What happened to a config during 'ncm update'
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateOutcome {
+    UpToDate,
+    Updated { commits: Vec<String>, lockfiles: Vec<String> },
+    Skipped(&'static str),
+}
+
+// Fetch and fast-forward the checked out branch to its upstream. Uncommitted
+// changes, a detached HEAD and diverged branches are left alone.
+pub(crate) fn update(path: &Path) -> Result<UpdateOutcome> {
+    if is_dirty(path)? {
+        return Ok(UpdateOutcome::Skipped(INFO_UPDATE_DIRTY));
+    }
+
+    if current_branch(path)?.is_none() {
+        return Ok(UpdateOutcome::Skipped(INFO_UPDATE_DETACHED));
+    }
+
+    if git(Some(path), &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"]).is_err() {
+        return Ok(UpdateOutcome::Skipped(INFO_UPDATE_NO_UPSTREAM));
+    }
+
+    git(Some(path), &["fetch", "--quiet", "--tags"])?;
+
+    let (ahead, behind) = ahead_behind(path)?;
+    if behind == 0 {
+        return Ok(UpdateOutcome::UpToDate);
+    }
+    if ahead > 0 {
+        return Ok(UpdateOutcome::Skipped(INFO_UPDATE_DIVERGED));
+    }
+
+    let before = git(Some(path), &["rev-parse", "HEAD"])?;
+    git(Some(path), &["merge", "--ff-only", "--quiet", "@{u}"])?;
+
+    let range = format!("{before}..HEAD");
+    let commits = lines(&git(Some(path), &["log", "--oneline", "--no-decorate", &range])?);
+    let lockfiles = lines(&git(Some(path), &["diff", "--name-only", &before, "HEAD"])?)
+        .into_iter()
+        .filter(|file| LOCKFILES.iter().any(|lock| Path::new(file).ends_with(lock)))
+        .collect();
+
+    Ok(UpdateOutcome::Updated { commits, lockfiles })
+}
+
+// Tracked files with uncommitted changes, untracked files do not block a fast-forward
+pub(crate) fn is_dirty(path: &Path) -> Result<bool> {
+    Ok(!git(Some(path), &["status", "--porcelain", "--untracked-files=no"])?.is_empty())
+}
+
+// Commits HEAD is ahead of and behind its upstream
+pub(crate) fn ahead_behind(path: &Path) -> Result<(usize, usize)> {
+    let counts = git(Some(path), &["rev-list", "--left-right", "--count", "HEAD...@{u}"])?;
+    let mut counts = counts.split_whitespace().map(|c| c.parse::<usize>().unwrap_or_default());
+    Ok((counts.next().unwrap_or_default(), counts.next().unwrap_or_default()))
+}
+
+fn lines(output: &str) -> Vec<String> {
+    output.lines().filter(|l| !l.is_empty()).map(String::from).collect()
+}
+
+// --| Status -----------------------------------
+// Branch, upstream distance and local changes of a working tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitStatus {
+    pub head: String,
+    /// None when HEAD is detached
+    pub branch: Option<String>,
+    /// None when the branch has no upstream
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Uncommitted changes, including untracked files
+    pub dirty: bool,
+}
+
+pub(crate) fn is_repository(path: &Path) -> bool {
+    path.join(GIT_DIR).exists()
+}
+
+// Read everything with a single 'git status' call
+pub(crate) fn status(path: &Path) -> Result<GitStatus> {
+    let output = git(Some(path), &["status", "--porcelain=v2", "--branch"])?;
+    let mut status = GitStatus { head: String::new(), branch: None, ahead_behind: None, dirty: false };
+
+    for line in output.lines() {
+        match line.strip_prefix("# ") {
+            Some(header) => match header.split_once(' ') {
+                Some(("branch.oid", oid)) => status.head = oid.to_string(),
+                Some(("branch.head", head)) if head != "(detached)" => status.branch = Some(head.to_string()),
+                Some(("branch.ab", ab)) => {
+                    let mut counts = ab.split_whitespace().map(|c| c[1..].parse::<usize>().unwrap_or_default());
+                    status.ahead_behind = Some((counts.next().unwrap_or_default(), counts.next().unwrap_or_default()));
+                }
+                _ => {}
+            },
+            None => status.dirty = true,
+        }
+    }
+    Ok(status)
+}
+
+// A cached status is used while it is younger than GIT_STATUS_TTL and
+// the repository's HEAD and index have not been touched since. Editing a
+// tracked file touches neither, so dirty can be up to GIT_STATUS_TTL stale.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedStatus {
+    fingerprint: Vec<u64>,
+    checked_at: DateTime<Utc>,
+    status: GitStatus,
+}
+
+// Status of every path that is a git repository, looked up in parallel
+// and cached in cache_path. Other paths are None.
+pub(crate) fn statuses(paths: &[PathBuf], cache_path: &Path) -> Vec<Option<GitStatus>> {
+    let mut cache: HashMap<String, CachedStatus> = std::fs::read_to_string(cache_path).ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+
+    let now = Utc::now();
+    let fresh = |path: &Path| -> Option<GitStatus> {
+        let cached = cache.get(path.to_string_lossy().as_ref())?;
+        let valid = now - cached.checked_at < Duration::seconds(GIT_STATUS_TTL) && cached.fingerprint == fingerprint(path);
+        valid.then(|| cached.status.clone())
+    };
+
+    // --| (status, whether it was looked up now rather than taken from the cache)
+    let results: Vec<Option<(GitStatus, bool)>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = paths.iter()
+            .map(|path| match (is_repository(path), fresh(path)) {
+                (false, _) => None,
+                (true, Some(status)) => Some(Ok(status)),
+                (true, None) => Some(Err(scope.spawn(|| status(path).ok()))),
+            })
+            .collect();
+
+        handles.into_iter()
+            .map(|handle| match handle {
+                None => None,
+                Some(Ok(status)) => Some((status, false)),
+                Some(Err(handle)) => handle.join().ok().flatten().map(|status| (status, true)),
+            })
+            .collect()
+    });
+
+    for (path, result) in paths.iter().zip(&results) {
+        if let Some((status, true)) = result {
+            let cached = CachedStatus { fingerprint: fingerprint(path), checked_at: now, status: status.clone() };
+            cache.insert(path.to_string_lossy().to_string(), cached);
+        }
+    }
+
+    // --| The cache only speeds up the next list, failing to write it is not an error
+    if let Ok(raw) = serde_json::to_string(&cache) {
+        let _ = std::fs::write(cache_path, raw);
+    }
+    results.into_iter().map(|result| result.map(|(status, _)| status)).collect()
+}
+
+// Modification times of the files git touches on commit, checkout and status,
+// plus the work tree itself, which changes when files are added or removed
+fn fingerprint(path: &Path) -> Vec<u64> {
+    let git_dir = git_dir(path);
+    [path.to_path_buf(), git_dir.clone(), git_dir.join("HEAD"), git_dir.join("index")].iter()
+        .map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default())
+        .collect()
+}
+
+// In a worktree .git is a file pointing at the worktree's own git directory
+fn git_dir(path: &Path) -> PathBuf {
+    let dot_git = path.join(GIT_DIR);
+    let linked = std::fs::read_to_string(&dot_git).ok()
+        .and_then(|raw| raw.lines().find_map(|line| line.strip_prefix("gitdir:")).map(|dir| path.join(dir.trim())));
+    linked.unwrap_or(dot_git)
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Create a bare repository with a single commit on main containing init.lua,
+    // and return its file:// url
+    pub(crate) fn create_remote(root: &Path) -> String {
+        let work = root.join("work");
+        let bare = root.join("remote.git");
+        std::fs::create_dir_all(&work).unwrap();
+
+        git(Some(&work), &["init", "--quiet", "--initial-branch", "main"]).unwrap();
+        std::fs::write(work.join(INIT_LUA), "-- init").unwrap();
+        commit_all(&work, "initial");
+
+        git(None, &["clone", "--quiet", "--bare", work.to_str().unwrap(), bare.to_str().unwrap()]).unwrap();
+        format!("file://{}", bare.display())
+    }
+
+    pub(crate) fn commit_all(path: &Path, message: &str) {
+        git(Some(path), &["add", "-A"]).unwrap();
+        git(Some(path), &["-c", "user.name=ncm", "-c", "user.email=ncm@localhost", "commit", "--quiet", "-m", message]).unwrap();
+    }
+
+    // Test that a file:// remote is cloned and a ref can be checked out
+    #[test]
+    fn test_clone() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = create_remote(dir.path());
+
+        let destination = dir.path().join("clone");
+        clone(&url, None, &destination).unwrap();
+        assert!(destination.join(INIT_LUA).exists());
+        assert_eq!(current_branch(&destination).unwrap(), Some("main".to_string()));
+
+        let head = git(Some(&destination), &["rev-parse", "HEAD"]).unwrap();
+        let detached = dir.path().join("detached");
+        clone(&url, Some(&head), &detached).unwrap();
+        assert_eq!(current_branch(&detached).unwrap(), None);
+
+        assert!(clone(&url, Some("missing"), &dir.path().join("missing")).is_err());
+        assert!(!dir.path().join("missing").exists());
+
+        // --| Neither the url nor the ref may be taken for an option
+        let marker = dir.path().join("marker");
+        let upload_pack = format!("--upload-pack=touch {}", marker.display());
+        assert!(clone(&upload_pack, None, &dir.path().join("option")).is_err());
+        assert!(!marker.exists());
+        assert!(clone(&url, Some("--orphan=x"), &dir.path().join("option")).is_err());
+        assert!(!dir.path().join("option").exists());
+    }
+
+    // Test that a worktree is created for a new branch and pruned on removal
+    #[test]
+    fn test_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = create_remote(dir.path());
+        let repo = dir.path().join("clone");
+        clone(&url, None, &repo).unwrap();
+
+        let worktree = dir.path().join("experiment");
+        add_worktree(&repo, "experiment", &worktree).unwrap();
+        assert_eq!(current_branch(&worktree).unwrap(), Some("experiment".to_string()));
+        assert!(is_repository(&worktree));
+
+        let common = common_dir(&worktree).unwrap();
+        assert_eq!(common, repo.join(GIT_DIR).canonicalize().unwrap());
+        assert!(git_dir(&worktree).join("HEAD").is_file());
+        assert_ne!(git_dir(&worktree), worktree.join(GIT_DIR));
+
+        remove_worktree(&common, &worktree).unwrap();
+        assert!(!worktree.exists());
+        assert!(!git(Some(&repo), &["worktree", "list"]).unwrap().contains("experiment"));
+    }
+
+    // Test that revisions resolve to full commit ids
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = create_remote(dir.path());
+        let destination = dir.path().join("clone");
+        clone(&url, None, &destination).unwrap();
+
+        let head = head(&destination).unwrap();
+        assert_eq!(head.len(), 40);
+        assert_eq!(resolve(&destination, "main").unwrap(), head);
+        assert_eq!(short(&head).len(), SHORT_COMMIT);
+        assert!(resolve(&destination, "missing").is_err());
+    }
+
+    // Test that status reports branch, upstream distance and local changes, and that
+    // paths which are not repositories are skipped
+    #[test]
+    fn test_statuses() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = create_remote(dir.path());
+        let destination = dir.path().join("clone");
+        clone(&url, None, &destination).unwrap();
+
+        let paths = vec![destination.clone(), dir.path().to_path_buf()];
+        let cache_path = dir.path().join("cache.json");
+
+        let found = statuses(&paths, &cache_path);
+        let status = found[0].as_ref().unwrap();
+        assert_eq!(status.branch, Some("main".to_string()));
+        assert_eq!(status.ahead_behind, Some((0, 0)));
+        assert_eq!(status.head, head(&destination).unwrap());
+        assert!(!status.dirty);
+        assert!(found[1].is_none());
+        assert!(cache_path.exists());
+
+        std::fs::write(destination.join(INIT_LUA), "-- changed").unwrap();
+        commit_all(&destination, "local");
+        std::fs::write(destination.join("new.lua"), "").unwrap();
+
+        let status = statuses(&paths, &cache_path)[0].clone().unwrap();
+        assert_eq!(status.ahead_behind, Some((1, 0)));
+        assert!(status.dirty);
+    }
+
+    // Test that update fast-forwards, reports lockfile changes and skips local changes
+    #[test]
+    fn test_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = create_remote(dir.path());
+        let destination = dir.path().join("clone");
+        clone(&url, None, &destination).unwrap();
+        assert_eq!(update(&destination).unwrap(), UpdateOutcome::UpToDate);
+
+        // --| New upstream commit touching the lockfile
+        let work = dir.path().join("work");
+        std::fs::write(work.join(LOCKFILES[0]), "{}").unwrap();
+        commit_all(&work, "lock plugins");
+        git(Some(&work), &["push", "--quiet", &url, "main"]).unwrap();
+
+        match update(&destination).unwrap() {
+            UpdateOutcome::Updated { commits, lockfiles } => {
+                assert_eq!(commits.len(), 1);
+                assert_eq!(lockfiles, vec![LOCKFILES[0].to_string()]);
+            }
+            outcome => panic!("unexpected outcome {outcome:?}"),
+        }
+
+        std::fs::write(destination.join(INIT_LUA), "-- changed").unwrap();
+        assert_eq!(update(&destination).unwrap(), UpdateOutcome::Skipped(INFO_UPDATE_DIRTY));
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::*;
+use crate::errors::NcmError;
+
+// Switch History Container
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+// Switch History Entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub name: String,
+    pub loaded_at: DateTime<Utc>,
+}
+
+// --| Read History -----------------------------
+// Read the switch history, an absent file is treated as an empty history
+pub(crate) fn read_history(history_path: &Path) -> Result<History> {
+    if !history_path.exists() { return Ok(History::default()); }
+
+    let history_file = std::fs::read_to_string(history_path)?;
+    serde_json::from_str(&history_file).map_err(|e| anyhow!("{}: {e}", ERR_HISTORY_PARSE))
+}
+
+// --| Record Switch ----------------------------
+// Append a loaded configuration to the history, keeping at most HISTORY_LIMIT entries
+pub(crate) fn record_switch(history_path: &Path, name: &str) -> Result<()> {
+    let mut history = read_history(history_path)?;
+
+    history.entries.push(HistoryEntry { name: name.to_string(), loaded_at: Utc::now() });
+
+    if history.entries.len() > HISTORY_LIMIT {
+        let excess = history.entries.len() - HISTORY_LIMIT;
+        history.entries.drain(..excess);
+    }
+
+    let history_json = serde_json::to_string(&history)?;
+    std::fs::write(history_path, history_json).map_err(|e| anyhow!("{}: {e}", ERR_HISTORY_WRITE))
+}
+
+// --| Previous Config --------------------------
+// The most recently loaded configuration that is not `current`, like `cd -`
+pub(crate) fn previous_config(history_path: &Path, current: &str) -> Result<String> {
+    let history = read_history(history_path)?;
+
+    history.entries.iter().rev()
+        .find(|entry| entry.name != current)
+        .map(|entry| entry.name.to_string())
+        .ok_or_else(|| NcmError::NotFound(ERR_HISTORY_EMPTY.to_string()).into())
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use pretty_assertions::assert_eq;
+
+    // Test that switching back and forth alternates between the last two configs
+    #[test]
+    fn test_previous_config() {
+        let dir = tempdir().unwrap();
+        let history_path = dir.path().join(HISTORY_FILE);
+
+        assert!(previous_config(&history_path, "main").is_err());
+
+        record_switch(&history_path, "main").unwrap();
+        record_switch(&history_path, "lazyvim").unwrap();
+        assert_eq!(previous_config(&history_path, "lazyvim").unwrap(), "main");
+
+        record_switch(&history_path, "main").unwrap();
+        assert_eq!(previous_config(&history_path, "main").unwrap(), "lazyvim");
+
+        dir.close().unwrap();
+    }
+
+    // Test that the history is capped at HISTORY_LIMIT entries
+    #[test]
+    fn test_history_limit() {
+        let dir = tempdir().unwrap();
+        let history_path = dir.path().join(HISTORY_FILE);
+
+        for i in 0..HISTORY_LIMIT + 5 {
+            record_switch(&history_path, &format!("config_{i}")).unwrap();
+        }
+
+        let history = read_history(&history_path).unwrap();
+        assert_eq!(history.entries.len(), HISTORY_LIMIT);
+        assert_eq!(history.entries[0].name, "config_5");
+
+        dir.close().unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use anyhow::{anyhow, Result};
+
+use crate::configs;
+use crate::setup;
+use crate::constants::*;
+use crate::settings::Settings;
+use crate::configs::ConfigData;
+
+// --| Isolated Root ----------------------------
+// Configs launched directly, rather than through the nvim symlinks, get an
+// XDG root of their own in the config's ncm state directory. config/nvim and
+// data/nvim link to the config and its data directory, state and cache are
+// kept inside the root. Single-file configs get an empty config/nvim, the
+// file itself is passed with -u.
+pub(crate) fn isolated_root(cfg: &ConfigData, settings: &Settings) -> PathBuf {
+    settings.ncm_paths.state.join(&cfg.name)
+}
+
+pub(crate) fn prepare(cfg: &ConfigData, settings: &Settings) -> Result<PathBuf> {
+    let root = isolated_root(cfg, settings);
+    let data_dir = if cfg!(target_os = "windows") { NVIM_DATA } else { NVIM };
+
+    let config_dir = root.join(XDG_CONFIG).join(NVIM);
+    std::fs::create_dir_all(root.join(XDG_CONFIG))?;
+    match cfg.entry {
+        Some(_) => {
+            if config_dir.is_symlink() {
+                setup::remove_link(&config_dir)?;
+            }
+            std::fs::create_dir_all(&config_dir)?;
+        }
+        None => configs::create_symlink(config_dir, PathBuf::from(&cfg.path))?,
+    }
+
+    std::fs::create_dir_all(root.join(XDG_DATA))?;
+    match &cfg.data_path {
+        Some(data_path) => {
+            std::fs::create_dir_all(data_path)?;
+            configs::create_symlink(root.join(XDG_DATA).join(data_dir), PathBuf::from(data_path))?;
+        }
+        None => std::fs::create_dir_all(root.join(XDG_DATA).join(data_dir))?,
+    }
+
+    std::fs::create_dir_all(root.join(XDG_STATE).join(NVIM))?;
+    std::fs::create_dir_all(root.join(XDG_CACHE).join(NVIM))?;
+    Ok(root)
+}
+
+// --| Launch -----------------------------------
+// nvim with the XDG variables pointed at the isolated root of cfg
+pub(crate) fn command(cfg: &ConfigData, settings: &Settings, args: &[String]) -> Result<Command> {
+    let root = prepare(cfg, settings)?;
+
+    let mut command = isolated_command(&root, &[]);
+    if let Some(file) = cfg.entry_file() {
+        command.arg("-u").arg(file);
+    }
+    command.args(args);
+    Ok(command)
+}
+
+pub(crate) fn isolated_command(root: &Path, args: &[String]) -> Command {
+    let mut command = Command::new(NVIM);
+    command.env("XDG_CONFIG_HOME", root.join(XDG_CONFIG))
+        .env("XDG_DATA_HOME", root.join(XDG_DATA))
+        .env("XDG_STATE_HOME", root.join(XDG_STATE))
+        .env("XDG_CACHE_HOME", root.join(XDG_CACHE))
+        .args(args);
+    command
+}
+
+pub(crate) fn run(cfg: &ConfigData, settings: &Settings, args: &[String]) -> Result<ExitStatus> {
+    command(cfg, settings, args)?.status().map_err(|e| anyhow!("{}: {e}", ERR_NVIM_RUN))
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::GenericPaths;
+    use pretty_assertions::assert_eq;
+
+    // Test that the isolated root links to the config and its data directory
+    #[cfg(unix)]
+    #[test]
+    fn test_prepare() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Settings {
+            ncm_paths: GenericPaths { state: dir.path().join("state"), ..Default::default() },
+            ..Default::default()
+        };
+
+        let config = dir.path().join("config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::write(config.join(INIT_LUA), "").unwrap();
+
+        let cfg = ConfigData {
+            name: "trial".to_string(),
+            path: config.to_string_lossy().to_string(),
+            data_path: Some(dir.path().join("data").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        let root = prepare(&cfg, &settings).unwrap();
+        assert_eq!(root, dir.path().join("state").join("trial"));
+        assert!(root.join(XDG_CONFIG).join(NVIM).join(INIT_LUA).exists());
+        assert!(root.join(XDG_DATA).join(NVIM).is_symlink());
+        assert!(dir.path().join("data").is_dir());
+
+        // --| Preparing again replaces the links
+        prepare(&cfg, &settings).unwrap();
+        let command = isolated_command(&root, &["--version".to_string()]);
+        assert_eq!(command.get_program(), NVIM);
+    }
+
+    // Test that a single-file config is passed with -u and gets an empty config directory
+    #[cfg(unix)]
+    #[test]
+    fn test_command_single_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Settings {
+            ncm_paths: GenericPaths { state: dir.path().join("state"), ..Default::default() },
+            ..Default::default()
+        };
+        std::fs::write(dir.path().join("minimal.lua"), "").unwrap();
+
+        let cfg = ConfigData {
+            name: "minimal".to_string(),
+            path: dir.path().to_string_lossy().to_string(),
+            entry: Some("minimal.lua".to_string()),
+            ..Default::default()
+        };
+
+        let command = command(&cfg, &settings, &["--headless".to_string()]).unwrap();
+        let args: Vec<String> = command.get_args().map(|arg| arg.to_string_lossy().to_string()).collect();
+        assert_eq!(args, vec!["-u".to_string(), dir.path().join("minimal.lua").to_string_lossy().to_string(), "--headless".to_string()]);
+
+        let config_dir = isolated_root(&cfg, &settings).join(XDG_CONFIG).join(NVIM);
+        assert!(config_dir.is_dir() && !config_dir.is_symlink());
+    }
+}
@@ -2,9 +2,12 @@ use std::env::var;
 use std::fs::File;
 use std::str::FromStr;
 use log::{Level, Metadata, Record};
-use log::{LevelFilter};
-use simplelog::{ColorChoice, CombinedLogger, Config, TerminalMode, TermLogger, WriteLogger};
+use log::{LevelFilter, warn};
+use simplelog::{ColorChoice, CombinedLogger, Config, SharedLogger, TerminalMode, TermLogger, WriteLogger};
 
+use crate::constants::*;
+
+#[allow(dead_code)]
 struct SimpleLogger;
 
 impl log::Log for SimpleLogger {
@@ -21,16 +24,25 @@ impl log::Log for SimpleLogger {
     fn flush(&self) {}
 }
 
-pub (crate) fn initialize(){
+// Structured output owns stdout, so all log messages go to stderr instead.
+// An invalid NCM_LOG_LEVEL falls back to Info, and the log file is skipped when it can not be created.
+pub (crate) fn initialize(structured: bool){
     let log_level = var("NCM_LOG_LEVEL").unwrap_or_else(|_| "Info".to_string());
+    let level = LevelFilter::from_str(&log_level).ok();
 
     let mut temp_dir = std::env::temp_dir();
     temp_dir.push("ncm.log");
 
-    CombinedLogger::init(
-        vec![
-            TermLogger::new(LevelFilter::from_str(&log_level).unwrap(), Config::default(), TerminalMode::Mixed, ColorChoice::Auto),
-            WriteLogger::new(LevelFilter::Info, Config::default(), File::create(&temp_dir).unwrap()),
-        ]
-    ).expect("Could not initialize logger"); 
+    let mode = if structured { TerminalMode::Stderr } else { TerminalMode::Mixed };
+
+    let mut loggers: Vec<Box<dyn SharedLogger>> = vec![
+        TermLogger::new(level.unwrap_or(LevelFilter::Info), Config::default(), mode, ColorChoice::Auto),
+    ];
+    if let Ok(file) = File::create(&temp_dir) {
+        loggers.push(WriteLogger::new(LevelFilter::Info, Config::default(), file));
+    }
+
+    if CombinedLogger::init(loggers).is_ok() && level.is_none() {
+        warn!("{} '{log_level}', {}", ERR_LOG_LEVEL, HINT_LOG_LEVEL);
+    }
 }
@@ -5,12 +5,27 @@ mod configs;
 mod settings;
 mod constants;
 mod paths;
+mod history;
+mod cleanup;
+mod doctor;
+mod repair;
+mod status;
+mod output;
+mod errors;
+mod prompt;
+mod setup;
+mod templates;
+mod teardown;
+mod git;
+mod launch;
+mod trial;
+mod repro;
 
 use constants::*;
-use crate::cmds::{Commands, NvCfgArgs};
+use crate::cmds::{BackupAction, Commands, NvCfgArgs, SetupOptions};
 
-use anyhow::{Result};
 use clap::Parser;
+use crate::errors::{NcmError, Result};
 
 #[macro_use]
 extern crate log;
@@ -27,57 +42,165 @@ use crate::paths::EnvVariables;
 
 // --| Global Settings ---------------------
 lazy_static! {
-    pub static ref SETTINGS: RwLock<settings::Settings> = RwLock::new(settings::get_settings(&EnvVariables::default()));
+    pub static ref SETTINGS: RwLock<settings::Settings> = RwLock::new(settings::Settings::default());
 }
 
-fn main() -> Result<()> {
-    logger::initialize();
+fn main() {
+    // --| Parse Arguments -----------------
+    let args = NvCfgArgs::parse();
 
-    let settings = &mut SETTINGS.write().unwrap();
-    let setup_complete = settings.settings.getbool(NCM, SETUP_COMPLETE)
-        .unwrap()
-        .expect(ERR_SETTINGS_READ);
+    logger::initialize(args.format.is_structured());
 
-    // --| Check if setup is needed --------
-    if cmds::check_setup(settings, setup_complete).is_err() {
-        return Ok(());
+    if let Err(e) = run(&args) {
+        error!("{e}");
+        std::process::exit(e.exit_code());
     }
+}
 
-    let config_json = &settings.configs_path.to_str().unwrap().to_string();
-    let data_path = Option::from(settings.ncm_paths.local.to_str().unwrap().to_string());
-    let cache_path = Option::from(settings.ncm_paths.cache.to_str().unwrap().to_string());
+fn run(args: &NvCfgArgs) -> Result<()> {
+    let settings = &mut SETTINGS.write().map_err(|e| NcmError::Settings(e.to_string()))?;
+    **settings = settings::get_settings(&EnvVariables::default()).map_err(|e| NcmError::Settings(e.to_string()))?;
 
-    // --| Parse Arguments -----------------
-    let args = NvCfgArgs::parse();
+    // --| Check that setup has been run ----
+    // --| Doctor and repair have to be able to run on a broken installation
+    if !matches!(args.command, Commands::Doctor | Commands::Repair | Commands::Status { .. } | Commands::Setup { .. }) {
+        let setup_complete = settings.settings.getbool(NCM, SETUP_COMPLETE)
+            .map_err(NcmError::Settings)?
+            .ok_or_else(|| NcmError::Settings(ERR_SETTINGS_READ.to_string()))?;
+
+        cmds::require_setup(settings, setup_complete)?;
+    }
+
+    let config_json = &settings.configs_path.to_string_lossy().to_string();
+    let data_path = Option::from(settings.ncm_paths.local.to_string_lossy().to_string());
+    let cache_path = Option::from(settings.ncm_paths.cache.to_string_lossy().to_string());
 
     match &args.command {
         // --| Add Command -----------------
-        Commands::Add { name, path, description } => {
-            cmds::add_config(name, path, description, data_path, cache_path, config_json);
+        Commands::Add { worktree: Some(worktree), .. } => {
+            cmds::add_worktree_config(&worktree[0], &worktree[1], &worktree[2], settings)?;
+        }
+        Commands::Add { name: Some(name), git: Some(url), git_ref, description_flag, .. } => {
+            cmds::add_git_config(name, url, git_ref, description_flag, settings)?;
         }
+        Commands::Add { name: Some(name), path: Some(path), description, description_flag, .. } => {
+            let description = description.clone().or_else(|| description_flag.clone());
+            cmds::add_config(name, path, &description, data_path, cache_path, config_json)?;
+        }
+        Commands::Add { .. } => return Err(NcmError::InvalidArgs(ERR_ADD_SOURCE.to_string())),
 
         // --| Remove Command --------------
-        Commands::Remove { name } => {
-            configs::remove_config(name, config_json)?;
+        Commands::Remove { name, purge, include_config, trash } => {
+            cmds::remove_config(name, *purge, *include_config, *trash, args.yes, settings)?;
         }
 
         // --| List Command ----------------
-        Commands::List => {
-            cmds::list_configs(config_json);
+        Commands::List { tag, sort } => {
+            cmds::list_configs(config_json, tag, sort, args.format, &settings.ncm_cfg_path.join(GIT_STATUS_CACHE))?;
         }
 
         // --| Load Command ----------------
-        Commands::Load { name } => {
-            cmds::load_config(name, settings);
+        Commands::Load { name, tag } => {
+            cmds::load_config(name, tag, settings)?;
+        }
+
+        // --| Run Command -----------------
+        Commands::Run { name, args: nvim_args } => {
+            cmds::run_config(name, nvim_args, settings)?;
         }
 
         // --| Backup Command --------------
-        Commands::Backup { name } => {
-            cmds::initiate_backup(name, settings);
+        Commands::Backup { action: Some(BackupAction::List), .. } => {
+            cmds::list_backups(args.format, settings)?;
+        }
+
+        Commands::Backup { action: None, name, tag } => {
+            cmds::initiate_backup(name, tag, settings)?;
+        }
+
+        // --| History Command -------------
+        Commands::History { count } => {
+            cmds::list_history(*count, settings)?;
+        }
+
+        // --| Gc Command ------------------
+        Commands::Gc { delete, trash } => {
+            cmds::collect_garbage(*delete, *trash, args.yes, settings)?;
+        }
+
+        // --| Doctor Command --------------
+        Commands::Doctor => {
+            cmds::run_doctor(args.format, settings)?;
+        }
+
+        // --| Repair Command --------------
+        Commands::Repair => {
+            cmds::run_repair(args.yes, settings)?;
+        }
+
+        // --| New Command -----------------
+        Commands::New { name, template } => {
+            cmds::new_config(name, template, settings)?;
+        }
+
+        // --| Try Command -----------------
+        Commands::Try { source, name, ttl } => {
+            cmds::try_config(source, name, ttl, args.yes, settings)?;
+        }
+
+        // --| Repro Command ---------------
+        Commands::Repro { name, plugins, run, ttl } => {
+            cmds::repro_config(name, plugins, *run, ttl, settings)?;
+        }
+
+        // --| Update Command --------------
+        Commands::Update { name, .. } => {
+            cmds::update_configs(name, settings)?;
+        }
+
+        // --| Pin Command -----------------
+        Commands::Pin { name, rev } => {
+            cmds::pin_config(name, rev, config_json)?;
+        }
+
+        // --| Unpin Command ---------------
+        Commands::Unpin { name } => {
+            cmds::unpin_config(name, config_json)?;
+        }
+
+        // --| Teardown Command ------------
+        Commands::Teardown { name, archive, discard_others } => {
+            cmds::run_teardown(name, archive, *discard_others, args.yes, settings)?;
+        }
+
+        // --| Status Command --------------
+        Commands::Status { short } => {
+            cmds::show_status(*short, args.format, settings)?;
+        }
+
+        // --| Tag Command -----------------
+        Commands::Tag { action } => {
+            cmds::tag_config(action, config_json)?;
+        }
+
+        // --| Set Command -----------------
+        Commands::Set { name, description, path, data_path, interactive } => {
+            cmds::set_config(name, description, path, data_path, *interactive, settings)?;
         }
 
-        Commands::Setup => {
-            info!("Setup");
+        // --| Setup Command ---------------
+        Commands::Setup { non_interactive, config_root, name, no_backup, resume, rollback, from, template } => {
+            let options = SetupOptions {
+                interactive: !(*non_interactive || args.yes),
+                config_root: config_root.clone(),
+                name: name.clone(),
+                backup: !*no_backup,
+                resume: *resume,
+                rollback: *rollback,
+                from: from.clone(),
+                template: template.clone(),
+            };
+            cmds::run_setup(&options, settings)?;
         }
     }
 
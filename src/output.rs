@@ -0,0 +1,100 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::constants::*;
+
+// Bumped whenever a field is removed or changes meaning, adding fields does not
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable tables and messages
+    Table,
+    Json,
+    Yaml,
+    /// Tab separated values with a header row
+    Tsv,
+}
+
+impl OutputFormat {
+    pub fn is_structured(&self) -> bool {
+        *self != OutputFormat::Table
+    }
+}
+
+// Every structured document is wrapped with the contract version and the kind of data it holds
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    version: u32,
+    kind: &'a str,
+    data: &'a T,
+}
+
+// Flat representation of a document for tsv output
+pub trait TsvRows {
+    fn tsv_header(&self) -> Vec<&'static str>;
+    fn tsv_rows(&self) -> Vec<Vec<String>>;
+}
+
+// --| Emit -------------------------------------
+// Write a document to stdout in the requested structured format
+pub(crate) fn emit<T: Serialize + TsvRows>(format: OutputFormat, kind: &str, data: &T) -> Result<()> {
+    println!("{}", render(format, kind, data)?);
+    Ok(())
+}
+
+pub(crate) fn render<T: Serialize + TsvRows>(format: OutputFormat, kind: &str, data: &T) -> Result<String> {
+    let envelope = Envelope { version: FORMAT_VERSION, kind, data };
+
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&envelope)?,
+        OutputFormat::Yaml => serde_yaml::to_string(&envelope)?.trim_end().to_string(),
+        OutputFormat::Tsv | OutputFormat::Table => {
+            let mut lines = vec![data.tsv_header().join(TSV_SEPARATOR)];
+            for row in data.tsv_rows() {
+                let fields: Vec<String> = row.iter().map(|f| tsv_escape(f)).collect();
+                lines.push(fields.join(TSV_SEPARATOR));
+            }
+            lines.join("\n")
+        }
+    })
+}
+
+// Tabs and newlines inside a field would break the row
+fn tsv_escape(field: &str) -> String {
+    field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Serialize)]
+    struct Example {
+        name: String,
+    }
+
+    impl TsvRows for Example {
+        fn tsv_header(&self) -> Vec<&'static str> { vec!["name"] }
+        fn tsv_rows(&self) -> Vec<Vec<String>> { vec![vec![self.name.to_string()]] }
+    }
+
+    // Test that each format wraps or flattens the document as documented
+    #[test]
+    fn test_render() {
+        let example = Example { name: "a\tb".to_string() };
+
+        let json: serde_json::Value = serde_json::from_str(&render(OutputFormat::Json, "example", &example).unwrap()).unwrap();
+        assert_eq!(json["version"], FORMAT_VERSION);
+        assert_eq!(json["kind"], "example");
+        assert_eq!(json["data"]["name"], "a\tb");
+
+        let yaml = render(OutputFormat::Yaml, "example", &example).unwrap();
+        assert!(yaml.starts_with(&format!("version: {FORMAT_VERSION}\nkind: example")));
+
+        assert_eq!(render(OutputFormat::Tsv, "example", &example).unwrap(), "name\na\\tb");
+    }
+}
@@ -0,0 +1,39 @@
+use std::io::IsTerminal;
+use inquire::Confirm;
+
+use crate::constants::*;
+use crate::errors::{NcmError, Result};
+
+// --| Confirm ----------------------------------
+// Ask for confirmation, or accept right away when --yes was passed
+pub(crate) fn confirm(prompt: Confirm, yes: bool) -> Result<bool> {
+    if yes {
+        info!("{} {}", prompt.message, INFO_ASSUME_YES);
+        return Ok(true);
+    }
+
+    require_terminal(prompt.message)?;
+    Ok(prompt.prompt()?)
+}
+
+// --| Require Terminal -------------------------
+// Prompts can not be answered without a terminal, fail instead of waiting on input that never comes
+pub(crate) fn require_terminal(message: &str) -> Result<()> {
+    if std::io::stdin().is_terminal() {
+        Ok(())
+    } else {
+        Err(NcmError::NotInteractive(message.to_string()))
+    }
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that --yes answers without needing a terminal
+    #[test]
+    fn test_confirm_yes() {
+        assert!(confirm(Confirm::new("Continue?").with_default(false), true).unwrap());
+    }
+}
@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+use configparser::ini::Ini;
+
+use crate::configs;
+use crate::constants::*;
+use crate::settings::Settings;
+use crate::configs::{ConfigData, Configs};
+
+// Keys written to settings.ini, anything else is dropped when rewriting it
+const KNOWN_KEYS: [&str; 6] = [SETUP_COMPLETE, BACKUP_PATH, SETUP_STAGE, SETUP_NAME, SETUP_CONFIG_ROOT, STRICT_PINS];
+
+// Progress of an interrupted setup, kept as is so it can still be resumed,
+// and optional switches the user set by hand
+const CARRIED_KEYS: [&str; 4] = [SETUP_STAGE, SETUP_NAME, SETUP_CONFIG_ROOT, STRICT_PINS];
+
+// --| Repair Settings --------------------------
+// Rewrite settings.ini from defaults when it cannot be parsed, when
+// setup_complete is missing or invalid, or when it disagrees with the
+// nvim symlink. Values of known keys are kept where they are valid.
+pub(crate) fn repair_settings(settings: &mut Settings) -> Result<Option<String>> {
+    let raw = std::fs::read_to_string(&settings.settings_path).unwrap_or_default();
+    let parses = Ini::new().read(raw.clone()).is_ok();
+    let linked = settings.nvim_path.is_symlink();
+
+    let mut values: Vec<(String, String)> = Vec::new();
+    for line in raw.lines() {
+        let (key, value) = match line.split_once(['=', ':']) {
+            Some((key, value)) => (key.trim().to_lowercase(), value.trim().to_string()),
+            None => continue,
+        };
+
+        if KNOWN_KEYS.contains(&key.as_str()) && !values.iter().any(|(k, _)| k == &key) {
+            values.push((key, value));
+        }
+    }
+
+    let stored_complete = values.iter()
+        .find(|(k, _)| k == SETUP_COMPLETE)
+        .and_then(|(_, v)| v.parse::<bool>().ok());
+
+    // --| An interrupted setup may already have created the symlink
+    let in_progress = values.iter().any(|(k, _)| k == SETUP_STAGE);
+    let setup_complete = if in_progress { stored_complete.unwrap_or(false) } else { stored_complete.unwrap_or(linked) || linked };
+    if parses && stored_complete == Some(setup_complete) {
+        return Ok(None);
+    }
+
+    let backup_path = values.iter()
+        .find(|(k, _)| k == BACKUP_PATH)
+        .map(|(_, v)| v.to_string())
+        .unwrap_or(NONE.to_string());
+
+    let mut ini = Ini::new();
+    ini.set(NCM, SETUP_COMPLETE, Some(setup_complete.to_string()));
+    ini.set(NCM, BACKUP_PATH, Some(backup_path));
+    for (key, value) in values.iter().filter(|(k, _)| CARRIED_KEYS.contains(&k.as_str())) {
+        ini.set(NCM, key, Some(value.to_string()));
+    }
+
+    if let Some(parent) = settings.settings_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    ini.write(&settings.settings_path).map_err(|e| anyhow!("{}: {e}", ERR_SETTINGS_UWRITE))?;
+    settings.settings = ini;
+
+    Ok(Some(format!("{} ({}={setup_complete})", REPAIR_SETTINGS, SETUP_COMPLETE)))
+}
+
+// --| Repair Data Directories ------------------
+// Create data directories of registered configs which no longer exist
+pub(crate) fn repair_data_dirs(cfgs: &Configs) -> Result<Vec<PathBuf>> {
+    let mut created: Vec<PathBuf> = Vec::new();
+
+    for cfg in &cfgs.configs {
+        // --| Configs whose own directory is gone are handled by missing_configs
+        if !Path::new(&cfg.path).exists() { continue; }
+
+        if let Some(data_path) = &cfg.data_path {
+            let path = PathBuf::from(data_path);
+            if !path.exists() {
+                std::fs::create_dir_all(&path)?;
+                created.push(path);
+            }
+        }
+    }
+
+    Ok(created)
+}
+
+// --| Missing Configs --------------------------
+// Registered configs whose configuration directory no longer exists
+pub(crate) fn missing_configs(cfgs: &Configs) -> Vec<ConfigData> {
+    cfgs.configs.iter()
+        .filter(|cfg| !Path::new(&cfg.path).exists())
+        .cloned()
+        .collect()
+}
+
+// --| Repair Symlinks --------------------------
+// Links which were re-pointed, and real directories found in place of a link
+#[derive(Debug, Default)]
+pub(crate) struct SymlinkRepair {
+    pub repaired: Vec<String>,
+    pub refused: Vec<PathBuf>,
+}
+
+// Point the nvim config and data symlinks at the default configuration when
+// they are missing, dangling or point elsewhere. Real directories are left
+// alone and reported, without stopping the other links from being repaired.
+pub(crate) fn repair_symlinks(settings: &Settings, cfgs: &Configs) -> Result<SymlinkRepair> {
+    let cfg = cfgs.configs.iter()
+        .find(|cfg| cfg.name == cfgs.configs_default)
+        .ok_or_else(|| anyhow!("{} '{}'", DOCTOR_DEFAULT_MISSING, cfgs.configs_default))?;
+
+    let mut result = SymlinkRepair::default();
+    let mut links = vec![(&settings.nvim_path, PathBuf::from(&cfg.path))];
+    if let Some(data_path) = &cfg.data_path {
+        links.push((&settings.data_path, PathBuf::from(data_path)));
+    }
+
+    for (link, target) in links {
+        if link.exists() && !link.is_symlink() {
+            result.refused.push(link.to_path_buf());
+            continue;
+        }
+
+        let current = link.canonicalize().ok();
+        if current.is_some() && current == target.canonicalize().ok() { continue; }
+
+        configs::create_symlink(link.to_path_buf(), target.clone())?;
+        result.repaired.push(format!("{:?} -> {:?}", link, target));
+    }
+
+    Ok(result)
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use pretty_assertions::assert_eq;
+
+    fn test_settings(root: &Path) -> Settings {
+        Settings {
+            nvim_path: root.join("nvim"),
+            data_path: root.join("nvim-data"),
+            settings_path: root.join(SETTINGS_FILE),
+            ..Default::default()
+        }
+    }
+
+    fn test_configs(root: &Path) -> Configs {
+        let config_dir = root.join("configs").join("main");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(config_dir.join(INIT_LUA), "").unwrap();
+
+        Configs {
+            configs_default: "main".to_string(),
+            configs: vec![
+                ConfigData {
+                    name: "main".to_string(),
+                    path: config_dir.to_str().unwrap().to_string(),
+                    data_path: Some(root.join("data").join("main").to_str().unwrap().to_string()),
+                    ..Default::default()
+                },
+                ConfigData {
+                    name: "gone".to_string(),
+                    path: root.join("configs").join("gone").to_str().unwrap().to_string(),
+                    data_path: Some(root.join("data").join("gone").to_str().unwrap().to_string()),
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    // Test that a malformed settings.ini is rewritten, keeping known keys
+    #[test]
+    fn test_repair_settings() {
+        let dir = tempdir().unwrap();
+        let mut settings = test_settings(dir.path());
+
+        std::fs::write(&settings.settings_path, "[ncm\nsetup_complete = maybe\nbackup_path = /tmp/backups\nstray = 1").unwrap();
+
+        let repaired = repair_settings(&mut settings).unwrap();
+        assert!(repaired.is_some());
+
+        let mut ini = Ini::new();
+        ini.load(&settings.settings_path).unwrap();
+        assert_eq!(ini.getbool(NCM, SETUP_COMPLETE).unwrap(), Some(false));
+        assert_eq!(ini.get(NCM, BACKUP_PATH), Some("/tmp/backups".to_string()));
+        assert_eq!(ini.get(NCM, "stray"), None);
+
+        // --| A valid file is left alone -------
+        assert!(repair_settings(&mut settings).unwrap().is_none());
+
+        dir.close().unwrap();
+    }
+
+    // Test that missing data dirs are created and symlinks re-pointed
+    #[cfg(unix)]
+    #[test]
+    fn test_repair_links_and_dirs() {
+        let dir = tempdir().unwrap();
+        let settings = test_settings(dir.path());
+        let cfgs = test_configs(dir.path());
+
+        let created = repair_data_dirs(&cfgs).unwrap();
+        assert_eq!(created, vec![dir.path().join("data").join("main")]);
+
+        let missing: Vec<String> = missing_configs(&cfgs).into_iter().map(|c| c.name).collect();
+        assert_eq!(missing, vec!["gone".to_string()]);
+
+        // --| Dangling symlink -----------------
+        std::os::unix::fs::symlink(dir.path().join("configs").join("gone"), &settings.nvim_path).unwrap();
+
+        let result = repair_symlinks(&settings, &cfgs).unwrap();
+        assert_eq!(result.repaired.len(), 2);
+        assert!(result.refused.is_empty());
+        assert_eq!(settings.nvim_path.read_link().unwrap(), dir.path().join("configs").join("main").canonicalize().unwrap());
+
+        // --| Already correct -------------------
+        assert!(repair_symlinks(&settings, &cfgs).unwrap().repaired.is_empty());
+
+        // --| A real directory does not stop the other link from being repaired
+        std::fs::remove_file(&settings.nvim_path).unwrap();
+        std::fs::create_dir_all(&settings.nvim_path).unwrap();
+        std::fs::remove_file(&settings.data_path).unwrap();
+
+        let result = repair_symlinks(&settings, &cfgs).unwrap();
+        assert_eq!(result.refused, vec![settings.nvim_path.clone()]);
+        assert_eq!(result.repaired.len(), 1);
+        assert!(settings.data_path.is_symlink());
+
+        dir.close().unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+
+use crate::constants::*;
+use crate::errors::NcmError;
+use crate::trial::is_git_url;
+
+const REPRO_TEMPLATE: &str = include_str!("templates/repro/repro.lua");
+
+// --| Plugin Spec ------------------------------
+// owner/repo or a git url, anything that could break out of a lua string is rejected
+pub(crate) fn parse_spec(value: &str) -> std::result::Result<String, String> {
+    let value = value.trim();
+    let invalid = || format!("{} '{value}', {}", ERR_REPRO_SPEC, HINT_REPRO_SPEC);
+
+    if value.is_empty() || value.chars().any(|c| c.is_whitespace() || c == '"' || c == '\'' || c == '\\') {
+        return Err(invalid());
+    }
+
+    let short = value.split('/').count() == 2 && value.split('/').all(|part| !part.is_empty());
+    if short || is_git_url(value) {
+        Ok(value.to_string())
+    } else {
+        Err(invalid())
+    }
+}
+
+// --| Render -----------------------------------
+// repro.lua with one lazy.nvim spec per plugin
+pub(crate) fn render(plugins: &[String]) -> String {
+    let specs: Vec<String> = plugins.iter()
+        .map(|plugin| if is_git_url(plugin) {
+            format!("    {{ url = \"{plugin}\" }},")
+        } else {
+            format!("    {{ \"{plugin}\" }},")
+        })
+        .collect();
+
+    REPRO_TEMPLATE.replace(REPRO_PLUGINS, &specs.join("\n"))
+}
+
+// --| Write ------------------------------------
+// Write repro.lua into destination, which must not exist or be empty, along
+// with the .repro root its config, data, state and cache are kept in
+pub(crate) fn write(plugins: &[String], destination: &Path) -> Result<PathBuf> {
+    if destination.exists() && destination.read_dir()?.next().is_some() {
+        return Err(NcmError::invalid_path(ERR_TEMPLATE_DESTINATION, destination).into());
+    }
+
+    let root = destination.join(REPRO_ROOT);
+    for dir in [XDG_CONFIG, XDG_DATA, XDG_STATE, XDG_CACHE] {
+        std::fs::create_dir_all(root.join(dir))?;
+    }
+
+    let file = destination.join(REPRO_LUA);
+    std::fs::write(&file, render(plugins))?;
+    Ok(file)
+}
+
+// The command to paste into a bug report, quoted when the path has spaces
+pub(crate) fn command_line(file: &Path) -> String {
+    let file = file.to_string_lossy();
+    if file.contains(' ') {
+        format!("{NVIM} -u \"{file}\"")
+    } else {
+        format!("{NVIM} -u {file}")
+    }
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Test that specs are validated and rendered into the lazy.nvim spec list
+    #[test]
+    fn test_render() {
+        assert_eq!(parse_spec(" folke/flash.nvim ").unwrap(), "folke/flash.nvim");
+        assert!(parse_spec("https://example.com/plugin.git").is_ok());
+        assert!(parse_spec("flash.nvim").is_err());
+        assert!(parse_spec("folke/flash\".nvim").is_err());
+        assert!(parse_spec("a/b/c").is_err());
+
+        let plugins = vec!["folke/flash.nvim".to_string(), "https://example.com/plugin.git".to_string()];
+        let rendered = render(&plugins);
+        assert!(rendered.contains("    { \"folke/flash.nvim\" },\n    { url = \"https://example.com/plugin.git\" },"));
+        assert!(!rendered.contains(REPRO_PLUGINS));
+    }
+
+    // Test that the repro file and its isolated root are written, but never over an existing config
+    #[test]
+    fn test_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("repro");
+        let plugins = vec!["folke/flash.nvim".to_string()];
+
+        let file = write(&plugins, &destination).unwrap();
+        assert_eq!(file, destination.join(REPRO_LUA));
+        assert!(destination.join(REPRO_ROOT).join(XDG_STATE).is_dir());
+        assert!(write(&plugins, &destination).is_err());
+        assert_eq!(command_line(Path::new("/a b/repro.lua")), "nvim -u \"/a b/repro.lua\"");
+    }
+}
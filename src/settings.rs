@@ -16,6 +16,7 @@ pub struct Settings {
     pub cache_path: PathBuf,
     pub ncm_cfg_path: PathBuf,
     pub configs_path: PathBuf,
+    pub history_path: PathBuf,
     pub settings_path: PathBuf,
     pub env_vars: EnvVariables,
     /// Custom ncm paths in which to store moved nvim files
@@ -38,6 +39,7 @@ impl Settings {
             cache_path: PathBuf::new(),
             ncm_cfg_path: PathBuf::new(),
             configs_path: PathBuf::new(),
+            history_path: PathBuf::new(),
             settings_map: HashMap::new(),
             settings_path: PathBuf::new(),
             ncm_paths: GenericPaths::default(),
@@ -66,6 +68,9 @@ impl Settings {
         self.configs_path.push(&self.ncm_cfg_path);
         self.configs_path.push(CONFIGS_FILE);
 
+        self.history_path.push(&self.ncm_cfg_path);
+        self.history_path.push(HISTORY_FILE);
+
         self
     }
 
@@ -76,16 +81,16 @@ impl Settings {
         if !self.ncm_paths.local.exists() { std::fs::create_dir_all(&self.ncm_paths.local)?; }
 
         if !self.settings_path.exists() {
-            std::fs::create_dir_all(self.settings_path.parent().unwrap())?;
+            std::fs::create_dir_all(&self.ncm_cfg_path)?;
             self.settings.read(String::from(
                 "[ncm]
                     setup_complete = false
-            backup_path=none")).expect(ERR_SETTINGS_UREAD);
-            self.settings.write(&self.settings_path).expect(ERR_SETTINGS_UWRITE);
+            backup_path=none")).map_err(|e| anyhow!("{}: {e}", ERR_SETTINGS_UREAD))?;
+            self.write_settings()?;
         }
 
         if !self.configs_path.exists() {
-            std::fs::create_dir_all(self.configs_path.parent().unwrap())?;
+            std::fs::create_dir_all(&self.ncm_cfg_path)?;
             let mut file = std::fs::File::create(&self.configs_path)?;
             file.write_all(b"{\n    \"configs\": [\n    ],\n    \"default\": \"\"\n}")?;
         }
@@ -106,19 +111,22 @@ impl Default for Settings {
 
 // --| Create and load Settings -----------------
 // pub fn get_settings(config_home: &str, home: &str) -> Settings {
-pub fn get_settings(env_vars: &EnvVariables) -> Settings {
+pub fn get_settings(env_vars: &EnvVariables) -> Result<Settings> {
     let mut settings = Settings::new(env_vars);
-    let mut settings = settings.get_paths();
+    let settings = settings.get_paths();
     
-    settings.check_directories().expect(ERR_DIR_UCREATE);
-    settings.settings.load(&settings.settings_path).unwrap();
+    settings.check_directories().map_err(|e| anyhow!("{}: {e}", ERR_DIR_UCREATE))?;
+
+    // --| A malformed settings.ini is reported by 'ncm doctor' and fixed by 'ncm repair'
+    if let Err(e) = settings.settings.load(&settings.settings_path) {
+        warn!("{}: {e}", ERR_SETTINGS_UREAD);
+    }
 
     let mut config = Ini::new();
-    let map = config.load(&settings.settings_path).unwrap();
-    settings.settings_map = map;
+    settings.settings_map = config.load(&settings.settings_path).unwrap_or_default();
     
     debug!("Settings: {:?}", settings);
-    settings.to_owned()
+    Ok(settings.to_owned())
 }
 
 // --| Tests ------------------------------------
@@ -174,7 +182,7 @@ mod tests {
             xdg_config_home: tmp_config_home.to_string(),
         };
 
-        let settings = get_settings(&env_vars);
+        let settings = get_settings(&env_vars).unwrap();
 
         info!("settings: {:?}", settings);
 
@@ -0,0 +1,200 @@
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+
+use crate::cleanup;
+use crate::configs;
+use crate::constants::*;
+use crate::errors::NcmError;
+use crate::settings::Settings;
+
+// Steps of the first-time setup, in the order they are run. The last
+// finished step is stored in settings.ini so an interrupted setup can
+// be resumed or rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SetupStage {
+    Started,
+    BackedUp,
+    ConfigMoved,
+    DataCopied,
+    Registered,
+}
+
+impl SetupStage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SetupStage::Started => "started",
+            SetupStage::BackedUp => "backed_up",
+            SetupStage::ConfigMoved => "config_moved",
+            SetupStage::DataCopied => "data_copied",
+            SetupStage::Registered => "registered",
+        }
+    }
+
+    pub fn parse(label: &str) -> Option<SetupStage> {
+        [SetupStage::Started, SetupStage::BackedUp, SetupStage::ConfigMoved, SetupStage::DataCopied, SetupStage::Registered]
+            .into_iter()
+            .find(|stage| stage.label() == label)
+    }
+}
+
+// Where the original configuration is being moved to, and how far along that is
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupProgress {
+    pub stage: SetupStage,
+    pub name: String,
+    pub config_root: PathBuf,
+}
+
+impl SetupProgress {
+    pub fn config_path(&self) -> PathBuf {
+        self.config_root.join(&self.name)
+    }
+
+    pub fn data_path(&self, settings: &Settings) -> PathBuf {
+        settings.ncm_paths.local.join(&self.name)
+    }
+}
+
+// --| Progress ---------------------------------
+// The setup in progress, if any
+pub(crate) fn read_progress(settings: &Settings) -> Option<SetupProgress> {
+    let stage = SetupStage::parse(&settings.settings.get(NCM, SETUP_STAGE)?)?;
+    let name = settings.settings.get(NCM, SETUP_NAME)?;
+    let config_root = PathBuf::from(settings.settings.get(NCM, SETUP_CONFIG_ROOT)?);
+
+    Some(SetupProgress { stage, name, config_root })
+}
+
+pub(crate) fn write_progress(settings: &mut Settings, progress: &SetupProgress) -> Result<()> {
+    settings.settings.set(NCM, SETUP_STAGE, Some(progress.stage.label().to_string()));
+    settings.settings.set(NCM, SETUP_NAME, Some(progress.name.to_string()));
+    settings.settings.set(NCM, SETUP_CONFIG_ROOT, Some(progress.config_root.to_string_lossy().to_string()));
+    settings.write_settings()
+}
+
+pub(crate) fn clear_progress(settings: &mut Settings) -> Result<()> {
+    for key in [SETUP_STAGE, SETUP_NAME, SETUP_CONFIG_ROOT] {
+        settings.settings.remove_key(NCM, key);
+    }
+    settings.write_settings()
+}
+
+// --| Rollback ---------------------------------
+// Undo an interrupted setup, putting the original configuration and data
+// back where they were. The zip backup, if one was made, is kept.
+pub(crate) fn rollback(settings: &mut Settings, progress: &SetupProgress) -> Result<Vec<String>> {
+    let mut actions: Vec<String> = Vec::new();
+    let config_path = progress.config_path();
+    let data_path = progress.data_path(settings);
+
+    // --| Registry entry ----------------------
+    if progress.stage >= SetupStage::Registered {
+        match configs::remove_config(&progress.name, settings.configs_path.to_str().unwrap_or_default()) {
+            Ok(_) => actions.push(format!("{}: {:?}", INFO_CONFIGS_REMOVED, progress.name)),
+            Err(NcmError::ConfigNotFound(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    // --| Data, replaced by a symlink once the config was loaded
+    if settings.data_path.is_symlink() {
+        remove_link(&settings.data_path)?;
+        cleanup::move_path(&data_path, &settings.data_path)?;
+        actions.push(format!("{}: {:?}", INFO_ROLLBACK_RESTORED, settings.data_path));
+    } else if progress.stage >= SetupStage::ConfigMoved && data_path.exists() {
+        std::fs::remove_dir_all(&data_path)?;
+        actions.push(format!("{}: {:?}", INFO_ROLLBACK_REMOVED, data_path));
+    }
+
+    // --| Config ------------------------------
+    if settings.nvim_path.is_symlink() {
+        remove_link(&settings.nvim_path)?;
+    }
+
+    if config_path.exists() && !settings.nvim_path.exists() {
+        cleanup::move_path(&config_path, &settings.nvim_path)?;
+        actions.push(format!("{}: {:?}", INFO_ROLLBACK_RESTORED, settings.nvim_path));
+    } else if !settings.nvim_path.exists() {
+        return Err(anyhow!("{}: {:?}", ERR_ROLLBACK_CONFIG, config_path));
+    }
+
+    clear_progress(settings)?;
+    Ok(actions)
+}
+
+pub(crate) fn remove_link(path: &Path) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    std::fs::remove_dir(path)?;
+
+    #[cfg(not(target_os = "windows"))]
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::GenericPaths;
+    use pretty_assertions::assert_eq;
+
+    fn test_settings(root: &Path) -> Settings {
+        Settings {
+            nvim_path: root.join("nvim"),
+            data_path: root.join("nvim-data"),
+            configs_path: root.join(CONFIGS_FILE),
+            settings_path: root.join(SETTINGS_FILE),
+            ncm_paths: GenericPaths { local: root.join(NCM_DATA), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    // Test that progress survives a round trip through settings.ini
+    #[test]
+    fn test_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path());
+        assert!(read_progress(&settings).is_none());
+
+        let progress = SetupProgress {
+            stage: SetupStage::ConfigMoved,
+            name: "main".to_string(),
+            config_root: dir.path().join("configs"),
+        };
+        write_progress(&mut settings, &progress).unwrap();
+
+        settings.settings.load(&settings.settings_path).unwrap();
+        assert_eq!(read_progress(&settings), Some(progress));
+
+        clear_progress(&mut settings).unwrap();
+        settings.settings.load(&settings.settings_path).unwrap();
+        assert!(read_progress(&settings).is_none());
+    }
+
+    // Test that rolling back after the config was moved puts it back in place
+    #[test]
+    fn test_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path());
+
+        let progress = SetupProgress {
+            stage: SetupStage::DataCopied,
+            name: "main".to_string(),
+            config_root: dir.path().join("configs"),
+        };
+
+        std::fs::create_dir_all(progress.config_path()).unwrap();
+        std::fs::write(progress.config_path().join(INIT_LUA), "").unwrap();
+        std::fs::create_dir_all(progress.data_path(&settings)).unwrap();
+        std::fs::create_dir_all(&settings.data_path).unwrap();
+        write_progress(&mut settings, &progress).unwrap();
+
+        let actions = rollback(&mut settings, &progress).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert!(settings.nvim_path.join(INIT_LUA).exists());
+        assert!(!progress.config_path().exists());
+        assert!(!progress.data_path(&settings).exists());
+        assert!(read_progress(&settings).is_none());
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::constants::*;
+use crate::output::TsvRows;
+use crate::settings::Settings;
+use crate::configs::{ConfigData, Configs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathKind {
+    Config,
+    Data,
+    Cache,
+    State,
+}
+
+impl PathKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PathKind::Config => STATUS_CONFIG,
+            PathKind::Data => STATUS_DATA,
+            PathKind::Cache => STATUS_CACHE,
+            PathKind::State => STATUS_STATE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    /// Symlink into a registered config
+    Linked,
+    /// Symlink whose target does not belong to any registered config
+    Unregistered,
+    /// Symlink whose target no longer exists
+    Dangling,
+    /// Real directory, not managed by ncm
+    Unmanaged,
+    /// Nothing exists at the path
+    Missing,
+}
+
+impl LinkState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LinkState::Linked => "linked",
+            LinkState::Unregistered => "unregistered",
+            LinkState::Dangling => "dangling",
+            LinkState::Unmanaged => "unmanaged",
+            LinkState::Missing => "missing",
+        }
+    }
+}
+
+// Resolved state of one of the nvim paths
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkStatus {
+    pub kind: PathKind,
+    pub path: PathBuf,
+    pub target: Option<PathBuf>,
+    pub owner: Option<String>,
+    pub state: LinkState,
+}
+
+// Live switching state of the nvim paths
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    pub default: String,
+    /// The registered config the nvim config path belongs to
+    pub current: Option<String>,
+    /// Linked paths belong to different configs, or not to the stored default
+    pub drift: bool,
+    pub links: Vec<LinkStatus>,
+}
+
+impl TsvRows for Status {
+    fn tsv_header(&self) -> Vec<&'static str> {
+        vec!["kind", "path", "target", "config", "state"]
+    }
+
+    fn tsv_rows(&self) -> Vec<Vec<String>> {
+        self.links.iter().map(|link| vec![
+            link.kind.label().to_string(),
+            link.path.to_string_lossy().to_string(),
+            link.target.as_ref().map(|t| t.to_string_lossy().to_string()).unwrap_or_default(),
+            link.owner.clone().unwrap_or_default(),
+            link.state.label().to_string(),
+        ]).collect()
+    }
+}
+
+// --| Resolve Status ---------------------------
+// Resolve the real targets of the nvim config, data, cache and state paths
+pub(crate) fn resolve_status(settings: &Settings, cfgs: &Configs) -> Status {
+    let kinds = [
+        (PathKind::Config, &settings.nvim_paths.config),
+        (PathKind::Data, &settings.nvim_paths.local),
+        (PathKind::Cache, &settings.nvim_paths.cache),
+        (PathKind::State, &settings.nvim_paths.state),
+    ];
+
+    let links: Vec<LinkStatus> = kinds.iter()
+        .map(|(kind, path)| resolve_link(*kind, path, cfgs, settings))
+        .collect();
+
+    let current = links.iter()
+        .find(|l| l.kind == PathKind::Config)
+        .and_then(|l| l.owner.clone());
+
+    let drift = links.iter()
+        .filter(|l| l.state != LinkState::Unmanaged && l.state != LinkState::Missing)
+        .any(|l| l.owner.as_deref() != Some(cfgs.configs_default.as_str()));
+
+    Status { default: cfgs.configs_default.to_string(), current, drift, links }
+}
+
+pub(crate) fn resolve_link(kind: PathKind, path: &Path, cfgs: &Configs, settings: &Settings) -> LinkStatus {
+    let mut status = LinkStatus { kind, path: path.to_path_buf(), target: None, owner: None, state: LinkState::Missing };
+
+    if !path.is_symlink() {
+        if path.exists() { status.state = LinkState::Unmanaged; }
+        return status;
+    }
+
+    status.target = std::fs::read_link(path).ok();
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => {
+            status.state = LinkState::Dangling;
+            return status;
+        }
+    };
+
+    status.owner = cfgs.configs.iter()
+        .find(|cfg| owned_path(kind, cfg, settings).and_then(|p| p.canonicalize().ok()).is_some_and(|p| p == resolved))
+        .map(|cfg| cfg.name.to_string());
+
+    status.state = if status.owner.is_some() { LinkState::Linked } else { LinkState::Unregistered };
+    status
+}
+
+// The directory of a config which the nvim path of `kind` should point at
+pub(crate) fn owned_path(kind: PathKind, cfg: &ConfigData, settings: &Settings) -> Option<PathBuf> {
+    match kind {
+        PathKind::Config => Some(PathBuf::from(&cfg.path)),
+        PathKind::Data => cfg.data_path.as_ref().map(PathBuf::from),
+        PathKind::Cache => {
+            let root = cfg.cache_path.as_ref().map(PathBuf::from).unwrap_or(settings.ncm_paths.cache.clone());
+            Some(if root.ends_with(&cfg.name) { root } else { root.join(&cfg.name) })
+        }
+        PathKind::State => Some(settings.ncm_paths.state.join(&cfg.name)),
+    }
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use pretty_assertions::assert_eq;
+
+    // Test that a config and data symlink from different configs is flagged as drift
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_status() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        let mut settings = Settings::default();
+        settings.nvim_paths.config = root.join("nvim");
+        settings.nvim_paths.local = root.join("nvim-data");
+        settings.nvim_paths.cache = root.join("nvim-cache");
+        settings.nvim_paths.state = root.join("nvim-state");
+        settings.ncm_paths.cache = root.join("cache");
+        settings.ncm_paths.state = root.join("state");
+
+        let mut configs = Configs { configs_default: "a".to_string(), configs: Vec::new() };
+        for name in ["a", "b"] {
+            std::fs::create_dir_all(root.join("configs").join(name)).unwrap();
+            std::fs::create_dir_all(root.join("data").join(name)).unwrap();
+            configs.configs.push(ConfigData {
+                name: name.to_string(),
+                path: root.join("configs").join(name).to_str().unwrap().to_string(),
+                data_path: Some(root.join("data").join(name).to_str().unwrap().to_string()),
+                ..Default::default()
+            });
+        }
+
+        std::os::unix::fs::symlink(root.join("configs").join("a"), &settings.nvim_paths.config).unwrap();
+        std::os::unix::fs::symlink(root.join("data").join("a"), &settings.nvim_paths.local).unwrap();
+        std::fs::create_dir_all(&settings.nvim_paths.cache).unwrap();
+
+        let status = resolve_status(&settings, &configs);
+        assert_eq!(status.current, Some("a".to_string()));
+        assert_eq!(status.links[2].state, LinkState::Unmanaged);
+        assert_eq!(status.links[3].state, LinkState::Missing);
+        assert!(!status.drift);
+
+        // --| Data now comes from another config
+        std::fs::remove_file(&settings.nvim_paths.local).unwrap();
+        std::os::unix::fs::symlink(root.join("data").join("b"), &settings.nvim_paths.local).unwrap();
+
+        let status = resolve_status(&settings, &configs);
+        assert_eq!(status.links[1].owner, Some("b".to_string()));
+        assert!(status.drift);
+
+        dir.close().unwrap();
+    }
+}
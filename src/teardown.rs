@@ -0,0 +1,247 @@
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+
+use crate::backup;
+use crate::cleanup;
+use crate::git;
+use crate::cmds::copy_recursively;
+use crate::constants::*;
+use crate::setup;
+use crate::errors::NcmError;
+use crate::settings::Settings;
+use crate::configs::{ConfigData, Configs};
+
+// Everything teardown is going to do, worked out up front so it can be
+// shown before anything is touched
+#[derive(Debug, Clone)]
+pub struct TeardownPlan {
+    pub config: ConfigData,
+    pub archive: Vec<ConfigData>,
+    /// Other configs with a directory inside the ncm roots, deleted along with them
+    pub discard: Vec<ConfigData>,
+    pub remove: Vec<PathBuf>,
+}
+
+// --| Plan -------------------------------------
+pub(crate) fn plan(settings: &Settings, cfgs: &Configs, name: &str, archive: bool, discard_others: bool) -> Result<TeardownPlan> {
+    let config = cfgs.configs.iter()
+        .find(|cfg| cfg.name == name)
+        .cloned()
+        .ok_or_else(|| NcmError::ConfigNotFound(name.to_string()))?;
+
+    if let Some(file) = config.entry_file() {
+        return Err(NcmError::invalid_path(ERR_TEARDOWN_ENTRY, file).into());
+    }
+    if !Path::new(&config.path).is_dir() {
+        return Err(NcmError::invalid_path(ERR_DIR_CONFIG_VERIFICATION, &config.path).into());
+    }
+
+    // --| Real directories in place of the symlinks would be overwritten
+    for path in [&settings.nvim_path, &settings.data_path] {
+        if path.exists() && !path.is_symlink() && !is_empty_dir(path) {
+            return Err(NcmError::invalid_path(ERR_TEARDOWN_NOT_LINK, path).into());
+        }
+    }
+
+    let discard: Vec<ConfigData> = cfgs.configs.iter()
+        .filter(|cfg| cfg.name != name && !discarded_paths(cfg, settings).is_empty())
+        .cloned()
+        .collect();
+
+    // --| Never delete other configs unless they are archived or explicitly discarded
+    if !discard.is_empty() && !archive && !discard_others {
+        let names: Vec<&str> = discard.iter().map(|cfg| cfg.name.as_str()).collect();
+        return Err(anyhow!("{}: {}, {}", ERR_TEARDOWN_DISCARD, names.join(", "), HINT_TEARDOWN_DISCARD));
+    }
+
+    let archive = if archive {
+        cfgs.configs.iter()
+            .filter(|cfg| cfg.name != name && Path::new(&cfg.path).is_dir())
+            .cloned()
+            .collect()
+    } else { Vec::new() };
+
+    let remove = [
+        &settings.ncm_cfg_path,
+        &settings.ncm_paths.config, &settings.ncm_paths.local, &settings.ncm_paths.cache, &settings.ncm_paths.state,
+    ].into_iter()
+        .filter(|path| path.exists())
+        .cloned()
+        .collect();
+
+    Ok(TeardownPlan { config, archive, discard, remove })
+}
+
+// The directories of cfg that are deleted along with the ncm roots,
+// directories added from elsewhere are left where they are
+pub(crate) fn discarded_paths(cfg: &ConfigData, settings: &Settings) -> Vec<PathBuf> {
+    std::iter::once(&cfg.path).chain(cfg.data_path.as_ref())
+        .map(PathBuf::from)
+        .filter(|path| path.starts_with(&settings.ncm_paths.config) || path.starts_with(&settings.ncm_paths.local))
+        .collect()
+}
+
+// --| Archive ----------------------------------
+// Zip each configuration directory into archive_dir as <name>.zip
+pub(crate) fn archive_configs(configs: &[ConfigData], archive_dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(archive_dir)?;
+
+    let mut archives: Vec<PathBuf> = Vec::new();
+    for cfg in configs {
+        let destination = archive_dir.join(format!("{}.{}", cfg.name, ZIP));
+        backup::create_backup(Path::new(&cfg.path), &destination)
+            .map_err(|e| anyhow!("{}: {:?} {e}", ERR_BACKUP_CREATE, cfg.name))?;
+        archives.push(destination);
+    }
+    Ok(archives)
+}
+
+// --| Worktrees --------------------------------
+// Remove discarded worktree configs through git, so the repository they were
+// checked out from does not keep stale worktree entries
+pub(crate) fn remove_worktrees(configs: &[ConfigData]) -> Result<()> {
+    for cfg in configs.iter().filter(|cfg| cfg.worktree_of.is_some()) {
+        let path = Path::new(&cfg.path);
+        if !path.exists() { continue; }
+
+        let common_dir = git::common_dir(path).map_err(|e| NcmError::Git(e.to_string()))?;
+        git::remove_worktree(&common_dir, path).map_err(|e| NcmError::Git(e.to_string()))?;
+    }
+    Ok(())
+}
+
+// --| Materialize ------------------------------
+// Replace the nvim config and data symlinks with real directories holding the
+// chosen configuration. Directories inside the ncm roots are moved out, ones
+// registered from elsewhere are copied and left where they are.
+pub(crate) fn materialize(settings: &Settings, cfg: &ConfigData) -> Result<()> {
+    replace_link(&settings.nvim_path, Path::new(&cfg.path), &settings.ncm_paths.config)?;
+
+    match &cfg.data_path {
+        Some(data_path) if Path::new(data_path).is_dir() => {
+            replace_link(&settings.data_path, Path::new(data_path), &settings.ncm_paths.local)?;
+        }
+        _ => {
+            clear_path(&settings.data_path)?;
+            std::fs::create_dir_all(&settings.data_path)?;
+        }
+    }
+    Ok(())
+}
+
+fn replace_link(link: &Path, source: &Path, managed_root: &Path) -> Result<()> {
+    clear_path(link)?;
+
+    if source.starts_with(managed_root) {
+        cleanup::move_path(source, link)
+    } else {
+        copy_recursively(source, link).map_err(|e| anyhow!("{}: {:?} {e}", ERR_TEARDOWN_COPY, source))
+    }
+}
+
+// Remove the symlink (or empty directory) in the way of the real directory
+fn clear_path(path: &Path) -> Result<()> {
+    if path.is_symlink() {
+        setup::remove_link(path)?;
+    } else if is_empty_dir(path) {
+        std::fs::remove_dir(path)?;
+    }
+    Ok(())
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    path.is_dir() && path.read_dir().is_ok_and(|mut entries| entries.next().is_none())
+}
+
+// --| Remove State -----------------------------
+pub(crate) fn remove_state(paths: &[PathBuf]) -> Result<()> {
+    for path in paths {
+        if path.exists() {
+            std::fs::remove_dir_all(path).map_err(|e| anyhow!("{}: {:?} {e}", ERR_CLEANUP_REMOVE, path))?;
+        }
+    }
+    Ok(())
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::GenericPaths;
+    use pretty_assertions::assert_eq;
+
+    fn test_settings(root: &Path) -> Settings {
+        Settings {
+            nvim_path: root.join("nvim"),
+            data_path: root.join("nvim-data"),
+            ncm_cfg_path: root.join(NCM_DIR),
+            ncm_paths: GenericPaths {
+                config: root.join(NCM_DATA),
+                local: root.join("ncm-data"),
+                cache: root.join("ncm-cache"),
+                state: root.join("ncm-state"),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn test_config(settings: &Settings, name: &str) -> ConfigData {
+        let path = settings.ncm_paths.config.join(name);
+        let data_path = settings.ncm_paths.local.join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::create_dir_all(&data_path).unwrap();
+        std::fs::write(path.join(INIT_LUA), name).unwrap();
+
+        ConfigData {
+            name: name.to_string(),
+            path: path.to_string_lossy().to_string(),
+            data_path: Some(data_path.to_string_lossy().to_string()),
+            ..Default::default()
+        }
+    }
+
+    // Test that a symlinked config ends up as real directories and the ncm state is removed
+    #[cfg(unix)]
+    #[test]
+    fn test_teardown() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path());
+        std::fs::create_dir_all(&settings.ncm_cfg_path).unwrap();
+
+        let main = test_config(&settings, "main");
+        let other = test_config(&settings, "other");
+        std::os::unix::fs::symlink(&main.path, &settings.nvim_path).unwrap();
+        std::os::unix::fs::symlink(main.data_path.as_ref().unwrap(), &settings.data_path).unwrap();
+
+        let cfgs = Configs { configs_default: main.name.to_string(), configs: vec![main, other] };
+        assert!(plan(&settings, &cfgs, "main", false, false).is_err());
+        assert_eq!(plan(&settings, &cfgs, "main", false, true).unwrap().discard.len(), 1);
+
+        let plan = plan(&settings, &cfgs, "main", true, false).unwrap();
+        assert_eq!(plan.archive.len(), 1);
+        assert_eq!(plan.remove.len(), 3);
+
+        let archives = archive_configs(&plan.archive, &dir.path().join("archive")).unwrap();
+        materialize(&settings, &plan.config).unwrap();
+        remove_state(&plan.remove).unwrap();
+
+        assert!(archives[0].exists());
+        assert!(!settings.nvim_path.is_symlink() && !settings.data_path.is_symlink());
+        assert_eq!(std::fs::read_to_string(settings.nvim_path.join(INIT_LUA)).unwrap(), "main");
+        assert!(!settings.ncm_cfg_path.exists() && !settings.ncm_paths.config.exists());
+    }
+
+    // Test that a real nvim directory is never overwritten
+    #[test]
+    fn test_plan_refuses_real_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path());
+        let main = test_config(&settings, "main");
+
+        std::fs::create_dir_all(&settings.nvim_path).unwrap();
+        std::fs::write(settings.nvim_path.join(INIT_LUA), "").unwrap();
+
+        let cfgs = Configs { configs_default: main.name.to_string(), configs: vec![main] };
+        assert!(plan(&settings, &cfgs, "main", false, false).is_err());
+    }
+}
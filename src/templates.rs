@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+
+use crate::constants::*;
+use crate::errors::NcmError;
+use crate::cmds::copy_recursively;
+
+// A configuration embedded in the binary, written out file by file
+pub struct Template {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub files: &'static [(&'static str, &'static str)],
+}
+
+pub const BUILTIN: [Template; 4] = [
+    Template {
+        name: "minimal",
+        description: "Sensible defaults, no plugins",
+        files: &[(INIT_LUA, include_str!("templates/minimal/init.lua"))],
+    },
+    Template {
+        name: "kickstart-style",
+        description: "Single init.lua with lazy.nvim, telescope, treesitter and lsp",
+        files: &[(INIT_LUA, include_str!("templates/kickstart-style/init.lua"))],
+    },
+    Template {
+        name: "lazy-bootstrap",
+        description: "lazy.nvim with options, keymaps and plugin specs split into lua/",
+        files: &[
+            (INIT_LUA, include_str!("templates/lazy-bootstrap/init.lua")),
+            ("lua/config/lazy.lua", include_str!("templates/lazy-bootstrap/lua/config/lazy.lua")),
+            ("lua/config/options.lua", include_str!("templates/lazy-bootstrap/lua/config/options.lua")),
+            ("lua/config/keymaps.lua", include_str!("templates/lazy-bootstrap/lua/config/keymaps.lua")),
+            ("lua/plugins/init.lua", include_str!("templates/lazy-bootstrap/lua/plugins/init.lua")),
+        ],
+    },
+    Template {
+        name: "vimscript",
+        description: "Sensible defaults in init.vim, no plugins",
+        files: &[(INIT_VIM, include_str!("templates/vimscript/init.vim"))],
+    },
+];
+
+// A template is either embedded in the binary, or a directory in the user
+// templates directory which is copied as is
+pub enum TemplateSource {
+    Builtin(&'static Template),
+    User { name: String, path: PathBuf },
+}
+
+impl TemplateSource {
+    pub fn name(&self) -> &str {
+        match self {
+            TemplateSource::Builtin(template) => template.name,
+            TemplateSource::User { name, .. } => name,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            TemplateSource::Builtin(template) => template.description.to_string(),
+            TemplateSource::User { path, .. } => format!("{} {}", INFO_TEMPLATE_USER, path.display()),
+        }
+    }
+}
+
+// --| Find Template ----------------------------
+// Directories in user_dir containing an init.lua or init.vim, sorted by name
+pub(crate) fn user_templates(user_dir: &Path) -> Vec<TemplateSource> {
+    let mut templates: Vec<TemplateSource> = std::fs::read_dir(user_dir).into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join(INIT_LUA).exists() || path.join(INIT_VIM).exists())
+        .map(|path| TemplateSource::User { name: path.file_name().unwrap_or_default().to_string_lossy().to_string(), path })
+        .collect();
+
+    templates.sort_by(|a, b| a.name().cmp(b.name()));
+    templates
+}
+
+// User templates first, then the built in ones they do not replace
+pub(crate) fn available(user_dir: &Path) -> Vec<TemplateSource> {
+    let mut templates = user_templates(user_dir);
+    for template in BUILTIN.iter() {
+        if !templates.iter().any(|t| t.name().eq_ignore_ascii_case(template.name)) {
+            templates.push(TemplateSource::Builtin(template));
+        }
+    }
+    templates
+}
+
+pub(crate) fn find(name: &str, user_dir: &Path) -> Option<TemplateSource> {
+    available(user_dir).into_iter().find(|t| t.name().eq_ignore_ascii_case(name))
+}
+
+// --| Write Template ---------------------------
+// Write the files of template into destination, which must not exist or be empty
+pub(crate) fn write_template(template: &TemplateSource, destination: &Path) -> Result<()> {
+    if destination.exists() && destination.read_dir()?.next().is_some() {
+        return Err(NcmError::invalid_path(ERR_TEMPLATE_DESTINATION, destination).into());
+    }
+
+    match template {
+        TemplateSource::Builtin(template) => {
+            for (file, contents) in template.files {
+                let path = destination.join(file);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, contents)?;
+            }
+        }
+        TemplateSource::User { path, .. } => copy_recursively(path, destination)?,
+    }
+    Ok(())
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Test that every built in template is written out and refuses to overwrite anything
+    #[test]
+    fn test_write_template() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for template in BUILTIN.iter() {
+            let destination = dir.path().join(template.name);
+            let source = find(template.name, &dir.path().join(TEMPLATES)).unwrap();
+
+            write_template(&source, &destination).unwrap();
+            assert!(destination.join(INIT_LUA).exists() || destination.join(INIT_VIM).exists());
+            assert!(write_template(&source, &destination).is_err());
+        }
+        assert!(dir.path().join("lazy-bootstrap/lua/plugins/init.lua").exists());
+    }
+
+    // Test that user templates are found and replace a built in one of the same name
+    #[test]
+    fn test_user_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let user_dir = dir.path().join(TEMPLATES);
+        std::fs::create_dir_all(user_dir.join("minimal")).unwrap();
+        std::fs::write(user_dir.join("minimal").join(INIT_LUA), "-- mine").unwrap();
+        std::fs::create_dir_all(user_dir.join("empty")).unwrap();
+
+        let names: Vec<String> = available(&user_dir).iter().map(|t| t.name().to_string()).collect();
+        assert_eq!(names, vec!["minimal", "kickstart-style", "lazy-bootstrap", "vimscript"]);
+
+        let destination = dir.path().join("new");
+        write_template(&find("minimal", &user_dir).unwrap(), &destination).unwrap();
+        assert_eq!(std::fs::read_to_string(destination.join(INIT_LUA)).unwrap(), "-- mine");
+    }
+}
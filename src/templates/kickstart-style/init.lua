@@ -0,0 +1,71 @@
+-- Single file Neovim configuration created by ncm, in the style of kickstart.nvim
+-- Everything lives in this file: options, keymaps and plugins managed by lazy.nvim
+
+vim.g.mapleader = " "
+vim.g.maplocalleader = " "
+
+-- [[ Options ]]
+local opt = vim.opt
+opt.number = true
+opt.mouse = "a"
+opt.clipboard = "unnamedplus"
+opt.breakindent = true
+opt.undofile = true
+opt.ignorecase = true
+opt.smartcase = true
+opt.signcolumn = "yes"
+opt.updatetime = 250
+opt.timeoutlen = 300
+opt.splitright = true
+opt.splitbelow = true
+opt.termguicolors = true
+
+-- [[ Keymaps ]]
+vim.keymap.set("n", "<Esc>", "<cmd>nohlsearch<cr>", { desc = "Clear search highlight" })
+vim.keymap.set("n", "[d", vim.diagnostic.goto_prev, { desc = "Previous diagnostic" })
+vim.keymap.set("n", "]d", vim.diagnostic.goto_next, { desc = "Next diagnostic" })
+
+-- [[ Install lazy.nvim ]]
+local lazypath = vim.fn.stdpath("data") .. "/lazy/lazy.nvim"
+if not vim.uv.fs_stat(lazypath) then
+  vim.fn.system({ "git", "clone", "--filter=blob:none", "--branch=stable", "https://github.com/folke/lazy.nvim.git", lazypath })
+end
+vim.opt.rtp:prepend(lazypath)
+
+-- [[ Plugins ]]
+require("lazy").setup({
+  { "folke/tokyonight.nvim", priority = 1000, config = function() vim.cmd.colorscheme("tokyonight-night") end },
+  { "folke/which-key.nvim", event = "VimEnter", opts = {} },
+  { "lewis6991/gitsigns.nvim", opts = {} },
+
+  {
+    "nvim-telescope/telescope.nvim",
+    branch = "0.1.x",
+    dependencies = { "nvim-lua/plenary.nvim" },
+    config = function()
+      local builtin = require("telescope.builtin")
+      vim.keymap.set("n", "<leader>sf", builtin.find_files, { desc = "Search files" })
+      vim.keymap.set("n", "<leader>sg", builtin.live_grep, { desc = "Search by grep" })
+      vim.keymap.set("n", "<leader><leader>", builtin.buffers, { desc = "Find buffers" })
+    end,
+  },
+
+  {
+    "nvim-treesitter/nvim-treesitter",
+    build = ":TSUpdate",
+    main = "nvim-treesitter.configs",
+    opts = { ensure_installed = { "lua", "vim", "vimdoc", "markdown" }, auto_install = true, highlight = { enable = true } },
+  },
+
+  {
+    "neovim/nvim-lspconfig",
+    dependencies = { "williamboman/mason.nvim", "williamboman/mason-lspconfig.nvim" },
+    config = function()
+      require("mason").setup()
+      require("mason-lspconfig").setup({ ensure_installed = { "lua_ls" } })
+      require("mason-lspconfig").setup_handlers({
+        function(server) require("lspconfig")[server].setup({}) end,
+      })
+    end,
+  },
+})
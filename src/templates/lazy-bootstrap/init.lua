@@ -0,0 +1,6 @@
+-- Neovim configuration created by ncm
+-- Options and keymaps live in lua/config, plugin specs in lua/plugins
+
+require("config.options")
+require("config.keymaps")
+require("config.lazy")
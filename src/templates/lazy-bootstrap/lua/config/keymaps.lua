@@ -0,0 +1,3 @@
+vim.keymap.set("n", "<leader>w", "<cmd>write<cr>", { desc = "Write buffer" })
+vim.keymap.set("n", "<leader>l", "<cmd>Lazy<cr>", { desc = "Open lazy.nvim" })
+vim.keymap.set("n", "<Esc>", "<cmd>nohlsearch<cr>", { desc = "Clear search highlight" })
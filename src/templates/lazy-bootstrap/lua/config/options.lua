@@ -0,0 +1,16 @@
+vim.g.mapleader = " "
+vim.g.maplocalleader = " "
+
+local opt = vim.opt
+opt.number = true
+opt.relativenumber = true
+opt.mouse = "a"
+opt.clipboard = "unnamedplus"
+opt.ignorecase = true
+opt.smartcase = true
+opt.expandtab = true
+opt.shiftwidth = 2
+opt.tabstop = 2
+opt.termguicolors = true
+opt.signcolumn = "yes"
+opt.undofile = true
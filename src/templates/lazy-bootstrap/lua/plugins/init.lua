@@ -0,0 +1,5 @@
+-- Every file in lua/plugins returns a list of lazy.nvim plugin specs
+return {
+  { "folke/tokyonight.nvim", lazy = false, priority = 1000, config = function() vim.cmd.colorscheme("tokyonight") end },
+  { "folke/which-key.nvim", event = "VeryLazy", opts = {} },
+}
@@ -0,0 +1,24 @@
+-- Minimal Neovim configuration created by ncm
+-- No plugins, just a few sensible defaults to build on
+
+vim.g.mapleader = " "
+vim.g.maplocalleader = " "
+
+local opt = vim.opt
+opt.number = true
+opt.relativenumber = true
+opt.mouse = "a"
+opt.clipboard = "unnamedplus"
+opt.ignorecase = true
+opt.smartcase = true
+opt.expandtab = true
+opt.shiftwidth = 4
+opt.tabstop = 4
+opt.termguicolors = true
+opt.signcolumn = "yes"
+opt.undofile = true
+opt.splitright = true
+opt.splitbelow = true
+
+vim.keymap.set("n", "<leader>w", "<cmd>write<cr>", { desc = "Write buffer" })
+vim.keymap.set("n", "<Esc>", "<cmd>nohlsearch<cr>", { desc = "Clear search highlight" })
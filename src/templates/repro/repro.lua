@@ -0,0 +1,24 @@
+-- Minimal reproduction created by ncm
+-- Run with: nvim -u repro.lua
+-- Everything is installed into .repro next to this file, your own
+-- configuration, plugins and state are not touched
+
+local root = vim.fn.fnamemodify(debug.getinfo(1, "S").source:sub(2), ":p:h") .. "/.repro"
+for _, name in ipairs({ "config", "data", "state", "cache" }) do
+  vim.env[("XDG_%s_HOME"):format(name:upper())] = root .. "/" .. name
+end
+
+-- Bootstrap lazy.nvim
+local lazypath = root .. "/plugins/lazy.nvim"
+if not (vim.uv or vim.loop).fs_stat(lazypath) then
+  vim.fn.system({ "git", "clone", "--filter=blob:none", "https://github.com/folke/lazy.nvim.git", lazypath })
+end
+vim.opt.rtp:prepend(lazypath)
+
+require("lazy").setup({
+  root = root .. "/plugins",
+  spec = {
+{{plugins}}
+    -- add anything else needed to reproduce the issue here
+  },
+})
@@ -0,0 +1,28 @@
+" Vimscript Neovim configuration created by ncm
+" No plugins, a few sensible defaults to build on
+
+let mapleader = " "
+let maplocalleader = " "
+
+set number
+set relativenumber
+set mouse=a
+set clipboard=unnamedplus
+set ignorecase
+set smartcase
+set expandtab
+set shiftwidth=4
+set tabstop=4
+set termguicolors
+set signcolumn=yes
+set undofile
+set splitright
+set splitbelow
+
+nnoremap <leader>w <cmd>write<cr>
+nnoremap <Esc> <cmd>nohlsearch<cr>
+
+augroup ncm_highlight_yank
+  autocmd!
+  autocmd TextYankPost * silent! lua vim.highlight.on_yank()
+augroup END
@@ -0,0 +1,131 @@
+use std::path::Path;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::cleanup;
+use crate::configs;
+use crate::constants::*;
+use crate::errors::NcmError;
+use crate::settings::Settings;
+use crate::configs::{ConfigData, Configs};
+
+// --| TTL --------------------------------------
+// Parse a positive duration such as 90m, 12h or 7d
+pub(crate) fn parse_ttl(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let invalid = || format!("{} '{value}', {}", ERR_TRY_TTL, HINT_TRY_TTL);
+
+    let unit_at = value.char_indices().last().map(|(i, _)| i).ok_or_else(invalid)?;
+    let (number, unit) = value.split_at(unit_at);
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    if number <= 0 {
+        return Err(invalid());
+    }
+
+    let ttl = match unit {
+        "m" => Duration::try_minutes(number),
+        "h" => Duration::try_hours(number),
+        "d" => Duration::try_days(number),
+        "w" => Duration::try_weeks(number),
+        _ => return Err(invalid()),
+    };
+    ttl.ok_or_else(|| format!("{} '{value}'", ERR_TRY_TTL_RANGE))
+}
+
+// When a trial started at now with ttl expires
+pub(crate) fn expires_at(ttl: &Option<Duration>, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    match ttl {
+        Some(ttl) => now.checked_add_signed(*ttl)
+            .map(Some)
+            .ok_or_else(|| NcmError::InvalidArgs(ERR_TRY_TTL_RANGE.to_string()).into()),
+        None => Ok(None),
+    }
+}
+
+// --| Source -----------------------------------
+// Anything that is not a local directory and looks like something git can clone
+pub(crate) fn is_git_url(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@") || source.ends_with(".git")
+}
+
+// try-<timestamp>, with a numbered suffix when several trials start in the same second
+pub(crate) fn default_name(configs: &Configs, now: DateTime<Utc>) -> String {
+    let stamped = format!("{}-{}", TRY_PREFIX, now.format(TRY_TIMESTAMP_FORMAT));
+
+    let mut name = stamped.to_string();
+    let mut suffix = 1;
+    while configs.configs.iter().any(|cfg| cfg.name == name) {
+        name = format!("{stamped}-{suffix}");
+        suffix += 1;
+    }
+    name
+}
+
+// --| Expired ----------------------------------
+// Trials whose --ttl has run out. Trials without a ttl never expire.
+pub(crate) fn expired(configs: &Configs, now: DateTime<Utc>) -> Vec<ConfigData> {
+    configs.configs.iter()
+        .filter(|cfg| cfg.temporary && cfg.expires_at.is_some_and(|expires| expires <= now))
+        .cloned()
+        .collect()
+}
+
+// --| Delete -----------------------------------
+// Remove a trial from the registry along with its data, state and cache. The
+// config directory is only removed when it was cloned into the ncm configs root.
+pub(crate) fn delete(cfg: &ConfigData, settings: &Settings, trash_dir: Option<&Path>) -> Result<()> {
+    let cloned = Path::new(&cfg.path).starts_with(&settings.ncm_paths.config);
+    let targets = cleanup::purge_targets(cfg, settings, cloned);
+
+    configs::remove_config(&cfg.name, &settings.configs_path.to_string_lossy())?;
+    for target in &targets {
+        cleanup::remove_target(&target.path, trash_dir)?;
+    }
+    Ok(())
+}
+
+// --| Tests ------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Test that ttl values are parsed and junk is rejected
+    #[test]
+    fn test_parse_ttl() {
+        assert_eq!(parse_ttl("90m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_ttl("7d").unwrap(), Duration::days(7));
+        assert!(parse_ttl("7").is_err());
+        assert!(parse_ttl("-5d").is_err());
+        assert!(parse_ttl("0h").is_err());
+        assert!(parse_ttl("9223372036854775807w").is_err());
+        assert!(expires_at(&Some(Duration::MAX), Utc::now()).is_err());
+        assert!(parse_ttl("d").is_err());
+        assert!(parse_ttl("").is_err());
+    }
+
+    // Test that only temporary configs past their expiry are returned
+    #[test]
+    fn test_expired() {
+        let now = Utc::now();
+        let config = |name: &str, temporary: bool, expires_at: Option<DateTime<Utc>>| ConfigData {
+            name: name.to_string(),
+            temporary,
+            expires_at,
+            ..Default::default()
+        };
+
+        let configs = Configs {
+            configs_default: String::new(),
+            configs: vec![
+                config("expired", true, Some(now - Duration::hours(1))),
+                config("running", true, Some(now + Duration::hours(1))),
+                config("no-ttl", true, None),
+                config("kept", false, Some(now - Duration::hours(1))),
+            ],
+        };
+
+        let names: Vec<String> = expired(&configs, now).into_iter().map(|cfg| cfg.name).collect();
+        assert_eq!(names, vec!["expired".to_string()]);
+    }
+}